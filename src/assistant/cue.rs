@@ -0,0 +1,59 @@
+use colored::Colorize;
+use notify_rust::Notification;
+use std::io::Write;
+
+// How Aurora tells the user that the command window closed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cue {
+    Notification,
+    Bell,
+    Silent,
+}
+
+impl Cue {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "notification" => Some(Cue::Notification),
+            "bell" => Some(Cue::Bell),
+            "none" | "" => Some(Cue::Silent),
+            _ => None,
+        }
+    }
+
+    pub fn play(self, message: &str) {
+        match self {
+            Cue::Notification => {
+                if let Err(err) = Notification::new()
+                    .appname("aurora")
+                    .summary("Aurora")
+                    .body(message)
+                    .show()
+                {
+                    println!(
+                        "{}",
+                        format!("[!] Unable to show notification: {err}").yellow()
+                    );
+                }
+            }
+            Cue::Bell => {
+                print!("\x07");
+                let _ = std::io::stdout().flush();
+            }
+            Cue::Silent => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cues() {
+        assert_eq!(Cue::parse("notification"), Some(Cue::Notification));
+        assert_eq!(Cue::parse("bell"), Some(Cue::Bell));
+        assert_eq!(Cue::parse("none"), Some(Cue::Silent));
+        assert_eq!(Cue::parse(""), Some(Cue::Silent));
+        assert_eq!(Cue::parse("siren"), None);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    commands::{
+        parser::{parse_command, parse_confirmation},
+        Command,
+    },
+    speech::{
+        command::{EarlyCommand, Pick},
+        confidence::{self, Verdict},
+        Transcript,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Accepted(Command),
+    Rejected(Command, f32),
+    // Asks before running; the answer comes with the next transcript.
+    Confirm(Command, f32),
+    Confirmed(Command),
+    Cancelled,
+}
+
+impl Decision {
+    // What the session gets, None while waiting for an answer.
+    pub fn command(&self, text: &str) -> Option<Command> {
+        match self {
+            Decision::Accepted(cmd) | Decision::Confirmed(cmd) => Some(cmd.clone()),
+            Decision::Rejected(..) | Decision::Cancelled => {
+                Some(Command::Unknown(text.to_string()))
+            }
+            Decision::Confirm(..) => None,
+        }
+    }
+}
+
+// Decides what a transcript from the command window turns into: confidence
+// checks, yes/no confirmations and commands that already ran early.
+pub struct CommandGate {
+    min_confidence: f32,
+    pending: Option<Command>,
+    early: EarlyCommand,
+}
+
+impl CommandGate {
+    pub fn new(sample_rate: u32, min_confidence: f32) -> Self {
+        Self {
+            min_confidence,
+            pending: None,
+            early: EarlyCommand::new(sample_rate, min_confidence),
+        }
+    }
+
+    pub fn min_confidence(&self) -> f32 {
+        self.min_confidence
+    }
+
+    pub fn is_confirming(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn partial(&mut self, partial: &Transcript, samples: usize) -> Option<Command> {
+        self.early.update(partial, samples)
+    }
+
+    pub fn decide(&mut self, transcript: &Transcript, pick: Option<&Pick>) -> Decision {
+        if let Some(pending) = self.pending.take() {
+            return if parse_confirmation(&transcript.text) == Some(true) {
+                Decision::Confirmed(pending)
+            } else {
+                Decision::Cancelled
+            };
+        }
+
+        let cmd = parse_command(&transcript.text);
+        match confidence::check(&cmd, transcript, pick, self.min_confidence) {
+            Verdict::Accept => Decision::Accepted(cmd),
+            Verdict::Reject(conf) => Decision::Rejected(cmd, conf),
+            Verdict::Confirm(conf) => {
+                self.pending = Some(cmd.clone());
+                Decision::Confirm(cmd, conf)
+            }
+        }
+    }
+
+    // True when the command already ran from a partial result.
+    pub fn already_ran(&mut self, cmd: &Command) -> bool {
+        self.early.finish(cmd)
+    }
+
+    // Forgets the current utterance but keeps a pending confirmation.
+    pub fn reset_early(&mut self) {
+        self.early.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.early.reset();
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        speech::{command::CommandRecognizer, scripted::ScriptedRecognizer},
+        testing::scored,
+    };
+
+    fn decide(gate: &mut CommandGate, transcript: Transcript) -> Decision {
+        let full = ScriptedRecognizer::new(1_000.0).result_at(0.1, transcript);
+        let mut rec = CommandRecognizer::new(Box::new(full), None);
+        let heard = rec.accept(&[0; 100], false).unwrap().unwrap();
+        gate.decide(&heard, rec.last_pick())
+    }
+
+    #[test]
+    fn confident_command_is_accepted() {
+        let mut gate = CommandGate::new(1_000, 0.6);
+        let decision = decide(&mut gate, scored("наступна пісня", 0.9));
+        assert_eq!(decision, Decision::Accepted(Command::AudioNext));
+        assert_eq!(decision.command("наступна пісня"), Some(Command::AudioNext));
+    }
+
+    #[test]
+    fn unsure_command_becomes_unknown() {
+        let mut gate = CommandGate::new(1_000, 0.6);
+        let decision = decide(&mut gate, scored("наступна пісня", 0.3));
+        assert_eq!(decision, Decision::Rejected(Command::AudioNext, 0.3));
+        assert_eq!(
+            decision.command("наступна пісня"),
+            Some(Command::Unknown(String::from("наступна пісня")))
+        );
+    }
+
+    #[test]
+    fn unsure_destructive_command_waits_for_an_answer() {
+        let mut gate = CommandGate::new(1_000, 0.6);
+        let decision = decide(&mut gate, scored("shutdown computer", 0.4));
+        assert_eq!(decision, Decision::Confirm(Command::Poweroff, 0.4));
+        assert_eq!(decision.command("shutdown computer"), None);
+        assert!(gate.is_confirming());
+
+        // A new utterance does not forget the question.
+        gate.reset_early();
+        assert_eq!(
+            decide(&mut gate, scored("yes", 0.4)),
+            Decision::Confirmed(Command::Poweroff)
+        );
+        assert!(!gate.is_confirming());
+
+        decide(&mut gate, scored("shutdown computer", 0.4));
+        assert_eq!(decide(&mut gate, scored("no", 0.9)), Decision::Cancelled);
+        decide(&mut gate, scored("shutdown computer", 0.4));
+        gate.reset();
+        assert!(!gate.is_confirming());
+    }
+
+    #[test]
+    fn command_fired_early_runs_once() {
+        let full = ScriptedRecognizer::new(1_000.0)
+            .partial_at(0.1, scored("пауза", 0.9))
+            .result_at(0.5, scored("пауза", 0.9));
+        let mut rec = CommandRecognizer::new(Box::new(full), None);
+        let mut gate = CommandGate::new(1_000, 0.6);
+
+        let mut fired = Vec::new();
+        let heard = loop {
+            match rec.accept(&[0; 100], false).unwrap() {
+                Some(t) => break t,
+                None => fired.extend(gate.partial(&rec.partial(), 100)),
+            }
+        };
+        assert_eq!(fired, vec![Command::AudioPause]);
+
+        let decision = gate.decide(&heard, rec.last_pick());
+        let cmd = decision.command(&heard.text).unwrap();
+        assert!(gate.already_ran(&cmd));
+        assert!(!gate.already_ran(&cmd));
+    }
+}
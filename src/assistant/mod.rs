@@ -0,0 +1,381 @@
+pub mod cue;
+pub mod gate;
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    commands::{Command, CommandResult},
+    speech::language::Language,
+};
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Follows the audio instead of the wall clock, so replayed files time out
+// at the same point in the recording no matter how fast they are decoded.
+#[derive(Clone)]
+pub struct SampleClock {
+    start: Instant,
+    sample_rate: u32,
+    samples: Rc<Cell<u64>>,
+}
+
+impl SampleClock {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            start: Instant::now(),
+            sample_rate,
+            samples: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn advance(&self, samples: usize) {
+        self.samples.set(self.samples.get() + samples as u64);
+    }
+}
+
+impl Clock for SampleClock {
+    fn now(&self) -> Instant {
+        let secs = self.samples.get() as f64 / self.sample_rate as f64;
+        self.start + Duration::from_secs_f64(secs)
+    }
+}
+
+impl Clock for Box<dyn Clock> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    Idle,
+    Armed,
+    InConversation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    WakeWord,
+    Command(Command),
+    Finished(CommandResult),
+    // Keeps the window open, e.g. while waiting for a confirmation.
+    Extend,
+    Tick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    ReadyForNext,
+    WaitingForWakeWord,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    Execute(Command),
+    Calibrate,
+    SwitchLanguage(Language),
+    EndConversation,
+    Timeout,
+    Prompt(Prompt),
+    Quit,
+}
+
+// Tracks whether Aurora is waiting for the wake word or for a command, and
+// for how long. A window of None never times out.
+pub struct Session<C: Clock> {
+    clock: C,
+    window: Option<Duration>,
+    continuous: bool,
+    state: State,
+    deadline: Option<Instant>,
+}
+
+impl<C: Clock> Session<C> {
+    pub fn new(clock: C, window: Option<Duration>, continuous: bool) -> Self {
+        Self {
+            clock,
+            window,
+            continuous,
+            state: State::Idle,
+            deadline: None,
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.state != State::Idle
+    }
+
+    // Armed and still inside the command window.
+    pub fn is_open(&self) -> bool {
+        self.is_armed() && !self.expired()
+    }
+
+    pub fn handle(&mut self, event: Event) -> Vec<Effect> {
+        match event {
+            Event::WakeWord => {
+                self.state = if self.continuous {
+                    State::InConversation
+                } else {
+                    State::Armed
+                };
+                self.extend();
+                Vec::new()
+            }
+            Event::Command(_) | Event::Tick if self.is_armed() && self.expired() => {
+                self.state = State::Idle;
+                vec![Effect::Timeout, Effect::Prompt(Prompt::WaitingForWakeWord)]
+            }
+            Event::Command(cmd) if self.is_armed() => vec![Effect::Execute(cmd)],
+            Event::Finished(result) if self.is_armed() => self.finished(result),
+            Event::Extend if self.is_armed() => {
+                self.extend();
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn finished(&mut self, result: CommandResult) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        match result {
+            CommandResult::Running => {}
+            CommandResult::Calibrate => effects.push(Effect::Calibrate),
+            // The new model is only swapped in between conversations.
+            CommandResult::SwitchLanguage(language) => {
+                effects.push(Effect::SwitchLanguage(language));
+                self.state = State::Armed;
+            }
+            CommandResult::EndConversation => {
+                effects.push(Effect::EndConversation);
+                self.state = State::Armed;
+            }
+            CommandResult::Quit => return vec![Effect::Quit],
+        }
+
+        if self.state == State::InConversation {
+            self.extend();
+            effects.push(Effect::Prompt(Prompt::ReadyForNext));
+        } else {
+            self.state = State::Idle;
+            effects.push(Effect::Prompt(Prompt::WaitingForWakeWord));
+        }
+        effects
+    }
+
+    fn extend(&mut self) {
+        self.deadline = self.window.map(|window| self.clock.now() + window);
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| self.clock.now() > deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(6);
+
+    #[derive(Clone)]
+    struct FakeClock(Rc<Cell<Instant>>);
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self(Rc::new(Cell::new(Instant::now())))
+        }
+
+        fn advance(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    fn session(continuous: bool) -> (Session<FakeClock>, FakeClock) {
+        let clock = FakeClock::new();
+        (Session::new(clock.clone(), Some(WINDOW), continuous), clock)
+    }
+
+    #[test]
+    fn wake_word_arms_for_a_single_command() {
+        let (mut s, _) = session(false);
+        assert_eq!(s.state(), State::Idle);
+        assert!(s.handle(Event::Command(Command::AudioPause)).is_empty());
+
+        s.handle(Event::WakeWord);
+        assert_eq!(s.state(), State::Armed);
+        assert_eq!(
+            s.handle(Event::Command(Command::AudioPause)),
+            vec![Effect::Execute(Command::AudioPause)]
+        );
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::Running)),
+            vec![Effect::Prompt(Prompt::WaitingForWakeWord)]
+        );
+        assert_eq!(s.state(), State::Idle);
+    }
+
+    #[test]
+    fn late_command_times_out() {
+        let (mut s, clock) = session(false);
+        s.handle(Event::WakeWord);
+        clock.advance(WINDOW + Duration::from_millis(1));
+
+        assert!(!s.is_open());
+        assert_eq!(
+            s.handle(Event::Command(Command::AudioNext)),
+            vec![Effect::Timeout, Effect::Prompt(Prompt::WaitingForWakeWord)]
+        );
+        assert_eq!(s.state(), State::Idle);
+    }
+
+    #[test]
+    fn tick_expires_the_window() {
+        let (mut s, clock) = session(true);
+        s.handle(Event::WakeWord);
+        clock.advance(WINDOW);
+        assert!(s.handle(Event::Tick).is_empty());
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(s.handle(Event::Tick)[0], Effect::Timeout);
+        assert!(s.handle(Event::Tick).is_empty());
+    }
+
+    #[test]
+    fn extend_keeps_the_window_open() {
+        let (mut s, clock) = session(false);
+        s.handle(Event::WakeWord);
+        clock.advance(Duration::from_secs(5));
+        s.handle(Event::Extend);
+        clock.advance(Duration::from_secs(5));
+        assert!(s.is_open());
+    }
+
+    #[test]
+    fn continuous_mode_stays_in_conversation() {
+        let (mut s, clock) = session(true);
+        s.handle(Event::WakeWord);
+        assert_eq!(s.state(), State::InConversation);
+
+        for _ in 0..3 {
+            clock.advance(Duration::from_secs(5));
+            s.handle(Event::Command(Command::VolumeUp));
+            assert_eq!(
+                s.handle(Event::Finished(CommandResult::Running)),
+                vec![Effect::Prompt(Prompt::ReadyForNext)]
+            );
+        }
+        assert!(s.is_open());
+    }
+
+    #[test]
+    fn end_conversation_returns_to_idle() {
+        let (mut s, _) = session(true);
+        s.handle(Event::WakeWord);
+        s.handle(Event::Command(Command::EndConversation));
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::EndConversation)),
+            vec![
+                Effect::EndConversation,
+                Effect::Prompt(Prompt::WaitingForWakeWord)
+            ]
+        );
+        assert_eq!(s.state(), State::Idle);
+    }
+
+    #[test]
+    fn quit_and_calibrate_are_forwarded() {
+        let (mut s, _) = session(true);
+        s.handle(Event::WakeWord);
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::Quit)),
+            vec![Effect::Quit]
+        );
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::Calibrate)),
+            vec![Effect::Calibrate, Effect::Prompt(Prompt::ReadyForNext)]
+        );
+    }
+
+    #[test]
+    fn language_switch_is_forwarded() {
+        let (mut s, _) = session(false);
+        s.handle(Event::WakeWord);
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::SwitchLanguage(
+                Language::English
+            ))),
+            vec![
+                Effect::SwitchLanguage(Language::English),
+                Effect::Prompt(Prompt::WaitingForWakeWord)
+            ]
+        );
+    }
+
+    #[test]
+    fn language_switch_ends_the_conversation() {
+        let (mut s, _) = session(true);
+        s.handle(Event::WakeWord);
+        assert_eq!(
+            s.handle(Event::Finished(CommandResult::SwitchLanguage(
+                Language::English
+            ))),
+            vec![
+                Effect::SwitchLanguage(Language::English),
+                Effect::Prompt(Prompt::WaitingForWakeWord)
+            ]
+        );
+        assert!(!s.is_armed());
+    }
+
+    #[test]
+    fn session_without_window_never_times_out() {
+        let clock = FakeClock::new();
+        let mut s = Session::new(clock.clone(), None, true);
+        s.handle(Event::WakeWord);
+        clock.advance(Duration::from_secs(3_600));
+        assert_eq!(
+            s.handle(Event::Command(Command::Screenshot)),
+            vec![Effect::Execute(Command::Screenshot)]
+        );
+    }
+
+    #[test]
+    fn sample_clock_follows_consumed_audio() {
+        let clock = SampleClock::new(16_000);
+        let mut s = Session::new(clock.clone(), Some(WINDOW), false);
+        s.handle(Event::WakeWord);
+
+        clock.advance(16_000 * 5);
+        assert!(s.handle(Event::Tick).is_empty());
+        clock.advance(16_000 * 2);
+        assert_eq!(
+            s.handle(Event::Tick),
+            vec![Effect::Timeout, Effect::Prompt(Prompt::WaitingForWakeWord)]
+        );
+    }
+}
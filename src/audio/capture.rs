@@ -0,0 +1,187 @@
+use anyhow::{bail, Result};
+use cpal::traits::DeviceTrait;
+use cpal::{FromSample, SampleFormat, SizedSample, I24, U24};
+use std::sync::mpsc;
+
+use super::{
+    downmix::{ChannelStrategy, Downmixer},
+    ring::AudioWriter,
+};
+
+const SCRATCH_SAMPLES: usize = 8192;
+
+pub fn build_input_stream(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sample_format: SampleFormat,
+    strategy: ChannelStrategy,
+    writer: AudioWriter,
+    errors: mpsc::Sender<String>,
+) -> Result<cpal::Stream> {
+    match sample_format {
+        SampleFormat::I8 => build_stream::<i8>(device, config, strategy, writer, errors),
+        SampleFormat::I16 => build_stream::<i16>(device, config, strategy, writer, errors),
+        SampleFormat::I24 => build_stream::<I24>(device, config, strategy, writer, errors),
+        SampleFormat::I32 => build_stream::<i32>(device, config, strategy, writer, errors),
+        SampleFormat::I64 => build_stream::<i64>(device, config, strategy, writer, errors),
+        SampleFormat::U8 => build_stream::<u8>(device, config, strategy, writer, errors),
+        SampleFormat::U16 => build_stream::<u16>(device, config, strategy, writer, errors),
+        SampleFormat::U24 => build_stream::<U24>(device, config, strategy, writer, errors),
+        SampleFormat::U32 => build_stream::<u32>(device, config, strategy, writer, errors),
+        SampleFormat::U64 => build_stream::<u64>(device, config, strategy, writer, errors),
+        SampleFormat::F32 => build_stream::<f32>(device, config, strategy, writer, errors),
+        SampleFormat::F64 => build_stream::<f64>(device, config, strategy, writer, errors),
+        format => bail!("Unsupported sample format: {format:?}"),
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    strategy: ChannelStrategy,
+    mut writer: AudioWriter,
+    errors: mpsc::Sender<String>,
+) -> Result<cpal::Stream>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let mut downmixer = Downmixer::new(strategy, config.channels as usize, config.sample_rate);
+    let mut scratch = Vec::with_capacity(SCRATCH_SAMPLES);
+    let err_fn = move |err| match err {
+        cpal::StreamError::BufferUnderrun => eprintln!("Stream error: {err}"),
+        err => {
+            let _ = errors.send(err.to_string());
+        }
+    };
+
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _info| {
+            downmixer.process_into(data, &mut scratch);
+            writer.push(&scratch);
+        },
+        err_fn,
+        None,
+    )?;
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpal::Sample;
+
+    fn downmix_to_i16<T>(data: &[T], channels: usize) -> Vec<i16>
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        Downmixer::new(ChannelStrategy::Average, channels, 16_000).process(data)
+    }
+
+    #[test]
+    fn f32_mono_passes_through() {
+        let out = downmix_to_i16(&[0.0f32, 0.5, -0.5, 1.0, -1.0], 1);
+        assert_eq!(out, vec![0, 16384, -16384, i16::MAX, i16::MIN]);
+    }
+
+    #[test]
+    fn f32_out_of_range_is_clamped() {
+        let out = downmix_to_i16(&[2.0f32, -3.0], 1);
+        assert_eq!(out, vec![i16::MAX, i16::MIN]);
+    }
+
+    #[test]
+    fn f64_converts() {
+        let out = downmix_to_i16(&[0.0f64, 0.25, -0.25], 1);
+        assert_eq!(out, vec![0, 8192, -8192]);
+    }
+
+    #[test]
+    fn i16_is_lossless() {
+        let input = [0i16, 1, -1, 1000, -1000, i16::MAX, i16::MIN];
+        assert_eq!(downmix_to_i16(&input, 1), input.to_vec());
+    }
+
+    #[test]
+    fn i8_scales_up() {
+        let out = downmix_to_i16(&[0i8, 64, -64, i8::MIN], 1);
+        assert_eq!(out, vec![0, 16384, -16384, i16::MIN]);
+    }
+
+    #[test]
+    fn i24_scales_down() {
+        let input = [
+            I24::new(0).unwrap(),
+            I24::new(1 << 22).unwrap(),
+            I24::new(-(1 << 22)).unwrap(),
+        ];
+        assert_eq!(downmix_to_i16(&input, 1), vec![0, 16384, -16384]);
+    }
+
+    #[test]
+    fn i32_scales_down() {
+        let out = downmix_to_i16(&[0i32, 1 << 30, -(1 << 30), i32::MIN], 1);
+        assert_eq!(out, vec![0, 16384, -16384, i16::MIN]);
+    }
+
+    #[test]
+    fn i64_scales_down() {
+        let out = downmix_to_i16(&[0i64, 1 << 62, -(1 << 62)], 1);
+        assert_eq!(out, vec![0, 16384, -16384]);
+    }
+
+    #[test]
+    fn u8_is_centered() {
+        let out = downmix_to_i16(&[128u8, 192, 64, 0], 1);
+        assert_eq!(out, vec![0, 16384, -16384, i16::MIN]);
+    }
+
+    #[test]
+    fn u16_is_centered() {
+        let out = downmix_to_i16(&[32768u16, 49152, 16384, 0], 1);
+        assert_eq!(out, vec![0, 16384, -16384, i16::MIN]);
+    }
+
+    #[test]
+    fn u24_is_centered() {
+        let input = [
+            U24::new(1 << 23).unwrap(),
+            U24::new(3 << 22).unwrap(),
+            U24::new(1 << 22).unwrap(),
+        ];
+        assert_eq!(downmix_to_i16(&input, 1), vec![0, 16384, -16384]);
+    }
+
+    #[test]
+    fn u32_is_centered() {
+        let out = downmix_to_i16(&[1u32 << 31, 3 << 30, 1 << 30], 1);
+        assert_eq!(out, vec![0, 16384, -16384]);
+    }
+
+    #[test]
+    fn u64_is_centered() {
+        let out = downmix_to_i16(&[1u64 << 63, 3 << 62, 1 << 62], 1);
+        assert_eq!(out, vec![0, 16384, -16384]);
+    }
+
+    #[test]
+    fn stereo_is_averaged() {
+        let out = downmix_to_i16(&[0.5f32, 0.0, -0.5, -0.5], 2);
+        assert_eq!(out, vec![8192, -16384]);
+    }
+
+    #[test]
+    fn stereo_i16_is_averaged() {
+        let out = downmix_to_i16(&[1000i16, 3000, -2000, 0], 2);
+        assert_eq!(out, vec![2000, -1000]);
+    }
+
+    #[test]
+    fn incomplete_frame_is_dropped() {
+        let out = downmix_to_i16(&[0.5f32, 0.5, 0.25], 2);
+        assert_eq!(out, vec![16384]);
+    }
+}
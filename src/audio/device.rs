@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use cpal::traits::{DeviceTrait, HostTrait};
+use strsim::jaro_winkler;
+
+use crate::normalizer::text::normalize;
+
+pub fn print_input_devices(host: &cpal::Host) -> Result<()> {
+    let default_name = host
+        .default_input_device()
+        .and_then(|d| d.description().ok())
+        .map(|d| d.to_string());
+
+    println!("{}", "[*] Input devices:".bold().magenta());
+
+    for (i, device) in host.input_devices()?.enumerate() {
+        let name = describe(&device);
+        let marker = if Some(&name) == default_name.as_ref() {
+            " (default)"
+        } else {
+            ""
+        };
+        println!("{}", format!("    {i}: {name}{marker}").magenta().bold());
+
+        match device.supported_input_configs() {
+            Ok(configs) => {
+                for config in configs {
+                    println!(
+                        "{}",
+                        format!(
+                            "        - channels={}, sample_rate={}..{}, sample_format={:?}",
+                            config.channels(),
+                            config.min_sample_rate(),
+                            config.max_sample_rate(),
+                            config.sample_format()
+                        )
+                        .magenta()
+                    );
+                }
+            }
+            Err(err) => println!("{}", format!("        [!] {err}").yellow()),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn select_input_device(
+    host: &cpal::Host,
+    wanted: &str,
+    threshold: f64,
+) -> Result<cpal::Device> {
+    if !wanted.trim().is_empty() {
+        let devices: Vec<cpal::Device> = host.input_devices()?.collect();
+        let names: Vec<String> = devices.iter().map(describe).collect();
+
+        if let Some(i) = match_device(wanted, &names, threshold) {
+            return devices
+                .into_iter()
+                .nth(i)
+                .context("Input device disappeared");
+        }
+
+        println!(
+            "{}\n{}",
+            format!("[!] Input device not found: {wanted}").yellow(),
+            "[*] Using default input device (run with --list-devices to see all)".cyan()
+        );
+    }
+
+    host.default_input_device().context("No input device found")
+}
+
+pub fn match_device(wanted: &str, names: &[String], threshold: f64) -> Option<usize> {
+    let wanted = normalize(wanted);
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let names: Vec<String> = names.iter().map(|n| normalize(n)).collect();
+
+    if let Some(i) = names.iter().position(|n| n.contains(&wanted)) {
+        return Some(i);
+    }
+
+    let span = wanted.split_whitespace().count();
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let tokens: Vec<&str> = name.split_whitespace().collect();
+            let best_window = tokens
+                .windows(span.min(tokens.len()).max(1))
+                .map(|w| jaro_winkler(&wanted, &w.join(" ")))
+                .fold(0.0, f64::max);
+            (i, jaro_winkler(&wanted, name).max(best_window))
+        })
+        .filter(|&(_, score)| score >= threshold)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+fn describe(device: &cpal::Device) -> String {
+    device
+        .description()
+        .map(|d| d.to_string())
+        .unwrap_or_else(|_| String::from("<unknown device>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        [
+            "Built-in Audio Analog Stereo (Intel) [Microphone]",
+            "Jabra Evolve2 65 (GN Audio) [Headset] via USB",
+            "ThinkPad Dock USB Audio [Microphone] via USB",
+            "Monitor of Built-in Audio",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn substring_match_is_case_insensitive() {
+        assert_eq!(match_device("jabra", &names(), 0.85), Some(1));
+        assert_eq!(match_device("THINKPAD DOCK", &names(), 0.85), Some(2));
+    }
+
+    #[test]
+    fn substring_prefers_first_device() {
+        assert_eq!(match_device("built-in audio", &names(), 0.85), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_match_tolerates_typos() {
+        assert_eq!(match_device("jabbra evolve", &names(), 0.85), Some(1));
+        assert_eq!(match_device("thinkpad dok", &names(), 0.85), Some(2));
+    }
+
+    #[test]
+    fn unknown_device_is_not_matched() {
+        assert_eq!(match_device("blue yeti", &names(), 0.85), None);
+    }
+
+    #[test]
+    fn empty_name_is_not_matched() {
+        assert_eq!(match_device("  ", &names(), 0.85), None);
+        assert_eq!(match_device("jabra", &[], 0.85), None);
+    }
+}
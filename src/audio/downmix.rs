@@ -0,0 +1,323 @@
+use colored::Colorize;
+use cpal::{FromSample, Sample};
+use std::collections::VecDeque;
+
+const SPEED_OF_SOUND: f32 = 343.0;
+const LOUDEST_HYSTERESIS: f32 = 1.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelStrategy {
+    Average,
+    Channel(usize),
+    Loudest,
+    Beamform { spacing_mm: f32, angle_deg: f32 },
+}
+
+impl ChannelStrategy {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        let mut parts = s.split(':');
+
+        match parts.next()? {
+            "" | "average" => Some(Self::Average),
+            "loudest" => Some(Self::Loudest),
+            "channel" => {
+                let n: usize = parts.next()?.parse().ok()?;
+                (n >= 1).then(|| Self::Channel(n - 1))
+            }
+            "beamform" => Some(Self::Beamform {
+                spacing_mm: parts.next()?.parse().ok()?,
+                angle_deg: parts.next().unwrap_or("0").parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub struct Downmixer {
+    strategy: ChannelStrategy,
+    channels: usize,
+    current: usize,
+    energy: Vec<f32>,
+    delay_lines: Vec<VecDeque<f32>>,
+}
+
+impl Downmixer {
+    pub fn new(strategy: ChannelStrategy, channels: usize, sample_rate: u32) -> Self {
+        let channels = channels.max(1);
+
+        let strategy = match strategy {
+            ChannelStrategy::Channel(n) if n >= channels => {
+                println!(
+                    "{}",
+                    format!(
+                        "[!] Channel {} requested but the device has {channels}, averaging instead",
+                        n + 1
+                    )
+                    .yellow()
+                );
+                ChannelStrategy::Average
+            }
+            strategy => strategy,
+        };
+
+        let delay_lines = match strategy {
+            ChannelStrategy::Beamform {
+                spacing_mm,
+                angle_deg,
+            } => steering_delays(channels, spacing_mm, angle_deg, sample_rate)
+                .into_iter()
+                .map(|d| VecDeque::from(vec![0.0; d]))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            strategy,
+            channels,
+            current: 0,
+            energy: vec![0.0; channels],
+            delay_lines,
+        }
+    }
+
+    pub fn process<T>(&mut self, data: &[T]) -> Vec<i16>
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        let mut out = Vec::with_capacity(data.len() / self.channels);
+        self.process_into(data, &mut out);
+        out
+    }
+
+    // Reuses `out` so the audio callback does not allocate once it has grown.
+    pub fn process_into<T>(&mut self, data: &[T], out: &mut Vec<i16>)
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        let channels = self.channels;
+        let frames = data.chunks_exact(channels);
+        let to_i16 = |x: f32| i16::from_sample(x.clamp(-1.0, 1.0));
+        out.clear();
+
+        match self.strategy {
+            ChannelStrategy::Average => out.extend(frames.map(|f| {
+                to_i16(f.iter().map(|s| s.to_sample::<f32>()).sum::<f32>() / channels as f32)
+            })),
+            ChannelStrategy::Channel(n) => {
+                out.extend(frames.map(|f| to_i16(f[n].to_sample::<f32>())))
+            }
+            ChannelStrategy::Loudest => {
+                self.current = self.loudest_channel(data);
+                let n = self.current;
+                out.extend(frames.map(|f| to_i16(f[n].to_sample::<f32>())))
+            }
+            ChannelStrategy::Beamform { .. } => {
+                let delay_lines = &mut self.delay_lines;
+                out.extend(frames.map(|f| {
+                    let sum: f32 = f
+                        .iter()
+                        .zip(delay_lines.iter_mut())
+                        .map(|(s, line)| {
+                            line.push_back(s.to_sample::<f32>());
+                            line.pop_front().unwrap_or(0.0)
+                        })
+                        .sum();
+                    to_i16(sum / channels as f32)
+                }))
+            }
+        }
+    }
+
+    fn loudest_channel<T>(&mut self, data: &[T]) -> usize
+    where
+        T: Sample,
+        f32: FromSample<T>,
+    {
+        let energy = &mut self.energy;
+        energy.fill(0.0);
+        for frame in data.chunks_exact(self.channels) {
+            for (e, s) in energy.iter_mut().zip(frame) {
+                let x = s.to_sample::<f32>();
+                *e += x * x;
+            }
+        }
+
+        let (best, &best_energy) = energy
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap_or((0, &0.0));
+
+        // Stay on the current channel unless another one is clearly louder.
+        if best_energy > energy[self.current] * LOUDEST_HYSTERESIS {
+            best
+        } else {
+            self.current
+        }
+    }
+}
+
+fn steering_delays(
+    channels: usize,
+    spacing_mm: f32,
+    angle_deg: f32,
+    sample_rate: u32,
+) -> Vec<usize> {
+    let step =
+        spacing_mm / 1000.0 * angle_deg.to_radians().sin() / SPEED_OF_SOUND * sample_rate as f32;
+    let arrivals: Vec<f32> = (0..channels).map(|i| i as f32 * step).collect();
+    let latest = arrivals.iter().cloned().fold(f32::MIN, f32::max);
+    arrivals
+        .iter()
+        .map(|a| (latest - a).round() as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_strategies() {
+        assert_eq!(ChannelStrategy::parse(""), Some(ChannelStrategy::Average));
+        assert_eq!(
+            ChannelStrategy::parse("Average"),
+            Some(ChannelStrategy::Average)
+        );
+        assert_eq!(
+            ChannelStrategy::parse("loudest"),
+            Some(ChannelStrategy::Loudest)
+        );
+        assert_eq!(
+            ChannelStrategy::parse("channel:3"),
+            Some(ChannelStrategy::Channel(2))
+        );
+        assert_eq!(
+            ChannelStrategy::parse("beamform:35:30"),
+            Some(ChannelStrategy::Beamform {
+                spacing_mm: 35.0,
+                angle_deg: 30.0
+            })
+        );
+        assert_eq!(
+            ChannelStrategy::parse("beamform:35"),
+            Some(ChannelStrategy::Beamform {
+                spacing_mm: 35.0,
+                angle_deg: 0.0
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid() {
+        for s in [
+            "channel",
+            "channel:0",
+            "channel:x",
+            "beamform",
+            "loud",
+            "beamform:a:b",
+        ] {
+            assert_eq!(ChannelStrategy::parse(s), None, "accepted {s}");
+        }
+    }
+
+    #[test]
+    fn average_uses_all_channels() {
+        let mut dm = Downmixer::new(ChannelStrategy::Average, 4, 16_000);
+        let out = dm.process(&[0.0f32, 0.0, 0.5, 0.5, 0.4, 0.4, 0.0, 0.0]);
+        assert_eq!(out, vec![8192, 6553]);
+    }
+
+    #[test]
+    fn channel_picks_one_channel() {
+        let mut dm = Downmixer::new(ChannelStrategy::Channel(2), 4, 16_000);
+        let out = dm.process(&[100i16, 200, 300, 400, 500, 600, 700, 800]);
+        assert_eq!(out, vec![300, 700]);
+    }
+
+    #[test]
+    fn out_of_range_channel_falls_back_to_average() {
+        let mut dm = Downmixer::new(ChannelStrategy::Channel(5), 2, 16_000);
+        assert_eq!(dm.process(&[100i16, 300]), vec![200]);
+    }
+
+    #[test]
+    fn loudest_follows_the_voice() {
+        let mut dm = Downmixer::new(ChannelStrategy::Loudest, 3, 16_000);
+        assert_eq!(
+            dm.process(&[10i16, 20, 5000, 10, 20, -5000]),
+            vec![5000, -5000]
+        );
+        assert_eq!(
+            dm.process(&[3000i16, 20, 100, -3000, 20, 100]),
+            vec![3000, -3000]
+        );
+    }
+
+    #[test]
+    fn loudest_has_hysteresis() {
+        let mut dm = Downmixer::new(ChannelStrategy::Loudest, 2, 16_000);
+        assert_eq!(dm.process(&[1000i16, 100]), vec![1000]);
+        assert_eq!(dm.process(&[1000i16, 1100]), vec![1000]);
+        assert_eq!(dm.process(&[100i16, 1100]), vec![1100]);
+    }
+
+    #[test]
+    fn process_into_reuses_buffer() {
+        let mut dm = Downmixer::new(ChannelStrategy::Average, 2, 16_000);
+        let mut out = vec![1, 2, 3, 4, 5];
+        dm.process_into(&[100i16, 300, -100, -300], &mut out);
+        assert_eq!(out, vec![200, -200]);
+        dm.process_into(&[0i16, 0], &mut out);
+        assert_eq!(out, vec![0]);
+    }
+
+    #[test]
+    fn broadside_beamform_is_average() {
+        let mut dm = Downmixer::new(
+            ChannelStrategy::Beamform {
+                spacing_mm: 40.0,
+                angle_deg: 0.0,
+            },
+            2,
+            16_000,
+        );
+        assert_eq!(
+            dm.process(&[1000i16, 3000, -1000, -3000]),
+            vec![2000, -2000]
+        );
+    }
+
+    #[test]
+    fn steering_delays_align_endfire_arrivals() {
+        // 343 mm between mics at 90 degrees is exactly one millisecond.
+        assert_eq!(steering_delays(3, 343.0, 90.0, 16_000), vec![32, 16, 0]);
+        assert_eq!(steering_delays(3, 343.0, -90.0, 16_000), vec![0, 16, 32]);
+    }
+
+    #[test]
+    fn beamform_sums_steered_pulse_coherently() {
+        let channels = 2;
+        let mut dm = Downmixer::new(
+            ChannelStrategy::Beamform {
+                spacing_mm: 343.0,
+                angle_deg: 90.0,
+            },
+            channels,
+            16_000,
+        );
+
+        // The pulse reaches mic 1 sixteen samples after mic 0.
+        let mut data = vec![0i16; 64 * channels];
+        data[10 * channels] = 10_000;
+        data[26 * channels + 1] = 10_000;
+
+        let out = dm.process(&data);
+        assert_eq!(out[26], 10_000);
+        assert_eq!(out.iter().filter(|&&s| s != 0).count(), 1);
+    }
+}
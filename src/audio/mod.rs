@@ -1 +1,25 @@
+pub mod capture;
+pub mod device;
+pub mod downmix;
+pub mod preroll;
+pub mod recorder;
 pub mod resample;
+pub mod ring;
+pub mod supervisor;
+pub mod wav;
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chunk {
+    Audio(Vec<i16>),
+    // Nothing arrived before the timeout.
+    Pending,
+    End,
+}
+
+pub trait AudioSource {
+    fn sample_rate(&self) -> u32;
+    fn device_name(&self) -> String;
+    fn next_chunk(&mut self, timeout: Duration) -> Chunk;
+}
@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+pub struct PreRoll {
+    buf: VecDeque<i16>,
+    capacity: usize,
+    sample_rate: u32,
+}
+
+impl PreRoll {
+    pub fn new(sample_rate: u32, ms: u32) -> Self {
+        let capacity = (sample_rate as u64 * ms as u64 / 1000) as usize;
+        Self {
+            buf: VecDeque::with_capacity(capacity),
+            capacity,
+            sample_rate,
+        }
+    }
+
+    pub fn push(&mut self, samples: &[i16]) {
+        let samples = &samples[samples.len().saturating_sub(self.capacity)..];
+        let overflow = (self.buf.len() + samples.len()).saturating_sub(self.capacity);
+        self.buf.drain(..overflow);
+        self.buf.extend(samples);
+    }
+
+    pub fn take(&mut self) -> Vec<i16> {
+        self.buf.drain(..).collect()
+    }
+
+    pub fn take_last(&mut self, ms: u32) -> Vec<i16> {
+        let n = (self.sample_rate as u64 * ms as u64 / 1000) as usize;
+        let start = self.buf.len().saturating_sub(n);
+        let tail = self.buf.range(start..).copied().collect();
+        self.buf.clear();
+        tail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_most_recent_audio() {
+        let mut preroll = PreRoll::new(1_000, 5);
+        preroll.push(&[1, 2, 3]);
+        preroll.push(&[4, 5, 6]);
+        assert_eq!(preroll.take(), vec![2, 3, 4, 5, 6]);
+        assert!(preroll.take().is_empty());
+    }
+
+    #[test]
+    fn oversized_push_keeps_its_tail() {
+        let mut preroll = PreRoll::new(1_000, 3);
+        preroll.push(&[1]);
+        preroll.push(&[2, 3, 4, 5, 6]);
+        assert_eq!(preroll.take(), vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn take_last_returns_tail_and_clears() {
+        let mut preroll = PreRoll::new(1_000, 10);
+        preroll.push(&[1, 2, 3, 4, 5, 6]);
+        assert_eq!(preroll.take_last(2), vec![5, 6]);
+        assert!(preroll.take().is_empty());
+
+        preroll.push(&[7]);
+        assert_eq!(preroll.take_last(5), vec![7]);
+    }
+}
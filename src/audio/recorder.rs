@@ -0,0 +1,292 @@
+use anyhow::Result;
+use colored::Colorize;
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::TARGET_SR;
+
+const SEGMENT_SECS: u32 = 60;
+const MAX_SEGMENTS: usize = 10;
+
+type Writer = hound::WavWriter<BufWriter<File>>;
+
+fn wav_spec(sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+pub struct RotatingWavWriter {
+    dir: PathBuf,
+    prefix: String,
+    sample_rate: u32,
+    max_samples: u32,
+    max_segments: usize,
+    writer: Option<Writer>,
+    segments: Vec<PathBuf>,
+    next_index: usize,
+}
+
+impl RotatingWavWriter {
+    pub fn new(
+        dir: &Path,
+        prefix: &str,
+        sample_rate: u32,
+        segment_secs: u32,
+        max_segments: usize,
+    ) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            sample_rate,
+            max_samples: sample_rate * segment_secs,
+            max_segments,
+            writer: None,
+            segments: Vec::new(),
+            next_index: 0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) -> Result<()> {
+        if sample_rate != self.sample_rate {
+            self.finish()?;
+            self.max_samples = self.max_samples / self.sample_rate * sample_rate;
+            self.sample_rate = sample_rate;
+        }
+        Ok(())
+    }
+
+    pub fn write(&mut self, samples: &[i16]) -> Result<()> {
+        for &s in samples {
+            if self
+                .writer
+                .as_ref()
+                .is_none_or(|w| w.duration() >= self.max_samples)
+            {
+                self.rotate()?;
+            }
+            if let Some(writer) = self.writer.as_mut() {
+                writer.write_sample(s)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        self.finish()?;
+
+        let path = self
+            .dir
+            .join(format!("{}-{:04}.wav", self.prefix, self.next_index));
+        self.next_index += 1;
+
+        self.writer = Some(hound::WavWriter::create(&path, wav_spec(self.sample_rate))?);
+        self.segments.push(path);
+
+        while self.segments.len() > self.max_segments {
+            let old = self.segments.remove(0);
+            let _ = fs::remove_file(old);
+        }
+        Ok(())
+    }
+}
+
+pub struct DebugRecorder {
+    dir: PathBuf,
+    raw: RotatingWavWriter,
+    resampled: RotatingWavWriter,
+    normalized: RotatingWavWriter,
+    clip: Option<Vec<i16>>,
+    session: u64,
+    clip_index: usize,
+    failed: bool,
+}
+
+impl DebugRecorder {
+    pub fn new(dir: impl AsRef<Path>, input_rate: u32) -> Result<Self> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir.join("clips"))?;
+
+        let writer =
+            |prefix, rate| RotatingWavWriter::new(dir, prefix, rate, SEGMENT_SECS, MAX_SEGMENTS);
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            raw: writer("raw", input_rate),
+            resampled: writer("resampled", TARGET_SR),
+            normalized: writer("normalized", TARGET_SR),
+            clip: None,
+            session: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            clip_index: 0,
+            failed: false,
+        })
+    }
+
+    pub fn raw(&mut self, samples: &[i16], sample_rate: u32) {
+        let result = self
+            .raw
+            .set_sample_rate(sample_rate)
+            .and_then(|_| self.raw.write(samples));
+        self.report(result);
+    }
+
+    pub fn resampled(&mut self, samples: &[i16]) {
+        let result = self.resampled.write(samples);
+        self.report(result);
+    }
+
+    pub fn normalized(&mut self, samples: &[i16]) {
+        let result = self.normalized.write(samples);
+        self.report(result);
+        if let Some(clip) = self.clip.as_mut() {
+            clip.extend_from_slice(samples);
+        }
+    }
+
+    pub fn start_clip(&mut self) {
+        self.clip = Some(Vec::new());
+    }
+
+    // For audio that was already recorded before the clip started.
+    pub fn extend_clip(&mut self, samples: &[i16]) {
+        if let Some(clip) = self.clip.as_mut() {
+            clip.extend_from_slice(samples);
+        }
+    }
+
+    pub fn finish_clip(&mut self, text: &str) {
+        let Some(clip) = self.clip.take() else {
+            return;
+        };
+        let result = self.write_clip(&clip, text);
+        self.report(result);
+    }
+
+    fn write_clip(&mut self, clip: &[i16], text: &str) -> Result<()> {
+        let stem = self
+            .dir
+            .join("clips")
+            .join(format!("clip-{}-{:03}", self.session, self.clip_index));
+        self.clip_index += 1;
+
+        let mut writer = hound::WavWriter::create(stem.with_extension("wav"), wav_spec(TARGET_SR))?;
+        for &s in clip {
+            writer.write_sample(s)?;
+        }
+        writer.finalize()?;
+
+        fs::write(stem.with_extension("txt"), format!("{text}\n"))?;
+        println!(
+            "{}",
+            format!(
+                "[*] Saved command clip {}",
+                stem.with_extension("wav").display()
+            )
+            .magenta()
+        );
+        Ok(())
+    }
+
+    fn report(&mut self, result: Result<()>) {
+        if let Err(err) = result {
+            if !self.failed {
+                println!("{}", format!("[!] Debug recording failed: {err}").yellow());
+                self.failed = true;
+            }
+        }
+    }
+}
+
+impl Drop for DebugRecorder {
+    fn drop(&mut self) {
+        let _ = self.raw.finish();
+        let _ = self.resampled.finish();
+        let _ = self.normalized.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    fn wav_len(path: &Path) -> u32 {
+        hound::WavReader::open(path).unwrap().duration()
+    }
+
+    #[test]
+    fn rotates_and_keeps_last_segments() {
+        let dir = temp_dir("rotate");
+        let mut writer = RotatingWavWriter::new(&dir, "raw", 100, 1, 2);
+        writer.write(&vec![1; 250]).unwrap();
+        writer.finish().unwrap();
+
+        assert!(!dir.join("raw-0000.wav").exists());
+        assert_eq!(wav_len(&dir.join("raw-0001.wav")), 100);
+        assert_eq!(wav_len(&dir.join("raw-0002.wav")), 50);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sample_rate_change_starts_new_segment() {
+        let dir = temp_dir("rate");
+        let mut writer = RotatingWavWriter::new(&dir, "raw", 100, 1, 5);
+        writer.write(&[1; 10]).unwrap();
+        writer.set_sample_rate(200).unwrap();
+        writer.write(&[1; 10]).unwrap();
+        writer.finish().unwrap();
+
+        let spec = hound::WavReader::open(dir.join("raw-0001.wav"))
+            .unwrap()
+            .spec();
+        assert_eq!(spec.sample_rate, 200);
+        assert_eq!(wav_len(&dir.join("raw-0000.wav")), 10);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn clip_is_saved_with_text() {
+        let dir = temp_dir("clip");
+        let mut recorder = DebugRecorder::new(&dir, 48_000).unwrap();
+
+        recorder.normalized(&[5; 10]);
+        recorder.extend_clip(&[6; 10]);
+        recorder.start_clip();
+        recorder.extend_clip(&[6; 5]);
+        recorder.normalized(&[7; 20]);
+        recorder.normalized(&[8; 30]);
+        recorder.finish_clip("відкрий термінал");
+        recorder.normalized(&[9; 10]);
+
+        let clip = dir
+            .join("clips")
+            .join(format!("clip-{}-000", recorder.session));
+        assert_eq!(wav_len(&clip.with_extension("wav")), 55);
+        assert_eq!(
+            fs::read_to_string(clip.with_extension("txt")).unwrap(),
+            "відкрий термінал\n"
+        );
+        assert_eq!(fs::read_dir(dir.join("clips")).unwrap().count(), 2);
+
+        drop(recorder);
+        assert_eq!(wav_len(&dir.join("normalized-0000.wav")), 70);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
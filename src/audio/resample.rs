@@ -1,48 +1,313 @@
+use std::f64::consts::PI;
+
+const ZERO_CROSSINGS: f64 = 24.0;
+const ROLLOFF: f64 = 0.88;
+const KAISER_BETA: f64 = 8.6;
+const MAX_PHASES: usize = 1024;
+
 #[derive(Debug, Clone)]
-pub struct LinearResampler {
-    in_rate: u32,
-    out_rate: u32,
-    pos: f64,
-    step: f64,
+pub struct PolyphaseResampler {
+    up: usize,
+    down: usize,
+    cutoff: f64,
+    half_taps: usize,
+    phases: Vec<Vec<f32>>,
+    scratch: Vec<f32>,
+    history: Vec<f32>,
+    pos: usize,
+    frac: usize,
 }
 
-impl LinearResampler {
+impl PolyphaseResampler {
     pub fn new(in_rate: u32, out_rate: u32) -> Self {
-        let step = in_rate as f64 / out_rate as f64;
-        Self {
-            in_rate,
-            out_rate,
-            pos: 0.0,
-            step,
+        let g = gcd(in_rate as usize, out_rate as usize).max(1);
+        let up = out_rate as usize / g;
+        let down = in_rate as usize / g;
+
+        let cutoff = ROLLOFF * (up as f64 / down as f64).min(1.0);
+        let half_taps = (ZERO_CROSSINGS / cutoff).ceil() as usize;
+
+        let mut rs = Self {
+            up,
+            down,
+            cutoff,
+            half_taps,
+            phases: Vec::new(),
+            scratch: vec![0.0; 2 * half_taps],
+            history: vec![0.0; half_taps],
+            pos: half_taps,
+            frac: 0,
+        };
+
+        // Ratios like 44100 -> 16001 would need thousands of tables,
+        // so above MAX_PHASES the kernel is evaluated per output sample.
+        if up <= MAX_PHASES {
+            rs.phases = (0..up)
+                .map(|p| {
+                    let mut taps = vec![0.0; 2 * half_taps];
+                    rs.fill_kernel(p, &mut taps);
+                    taps
+                })
+                .collect();
         }
+
+        rs
     }
 
     pub fn process(&mut self, input: &[i16]) -> Vec<i16> {
-        if input.len() < 2 || self.in_rate == self.out_rate {
+        if self.up == self.down {
             return input.to_vec();
         }
 
-        let estimated = ((input.len() as f64) / self.step).ceil() as usize;
+        self.history.extend(input.iter().map(|&s| s as f32));
+
+        let estimated = (input.len() * self.up).div_ceil(self.down);
         let mut out = Vec::with_capacity(estimated);
+        let taps = 2 * self.half_taps;
 
-        while (self.pos as usize) + 1 < input.len() {
-            let i = self.pos.floor() as usize;
-            let frac = self.pos - (i as f64);
+        while self.pos + self.half_taps < self.history.len() {
+            let start = self.pos + 1 - self.half_taps;
+            let window = &self.history[start..start + taps];
 
-            let a = input[i] as f64;
-            let b = input[i + 1] as f64;
+            let kernel = if self.phases.is_empty() {
+                let mut scratch = std::mem::take(&mut self.scratch);
+                self.fill_kernel(self.frac, &mut scratch);
+                self.scratch = scratch;
+                &self.scratch
+            } else {
+                &self.phases[self.frac]
+            };
 
-            let y = a + (b - a) * frac;
-            out.push(y.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16);
+            let y: f32 = window.iter().zip(kernel).map(|(x, h)| x * h).sum();
+            out.push(y.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16);
 
-            self.pos += self.step;
+            self.frac += self.down;
+            self.pos += self.frac / self.up;
+            self.frac %= self.up;
         }
 
-        self.pos -= input.len() as f64;
-        if self.pos < 0.0 {
-            self.pos = 0.0;
-        }
+        let consumed = (self.pos + 1 - self.half_taps).min(self.history.len());
+        self.history.drain(..consumed);
+        self.pos -= consumed;
 
         out
     }
+
+    fn fill_kernel(&self, phase: usize, taps: &mut [f32]) {
+        let offset = phase as f64 / self.up as f64;
+        let half = self.half_taps as f64;
+
+        let mut sum = 0.0;
+        for (k, tap) in taps.iter_mut().enumerate() {
+            let x = offset + half - 1.0 - k as f64;
+            let h = self.cutoff * sinc(self.cutoff * x) * kaiser(x / half);
+            *tap = h as f32;
+            sum += h;
+        }
+
+        for tap in taps.iter_mut() {
+            *tap /= sum as f32;
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn kaiser(x: f64) -> f64 {
+    if x.abs() > 1.0 {
+        return 0.0;
+    }
+    bessel_i0(KAISER_BETA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_BETA)
+}
+
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..64 {
+        term *= half / k as f64;
+        let t2 = term * term;
+        sum += t2;
+        if t2 < sum * 1e-16 {
+            break;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMPLITUDE: f64 = 16_000.0;
+
+    fn sine(freq: f64, rate: u32, secs: f64) -> Vec<i16> {
+        let n = (rate as f64 * secs) as usize;
+        (0..n)
+            .map(|i| (AMPLITUDE * (2.0 * PI * freq * i as f64 / rate as f64).sin()).round() as i16)
+            .collect()
+    }
+
+    fn settled(out: &[i16]) -> &[i16] {
+        let skip = out.len() / 4;
+        &out[skip..out.len() - skip]
+    }
+
+    fn tone_amplitude(signal: &[i16], freq: f64, rate: u32) -> f64 {
+        let (mut re, mut im) = (0.0, 0.0);
+        for (i, &s) in signal.iter().enumerate() {
+            let w = 2.0 * PI * freq * i as f64 / rate as f64;
+            re += s as f64 * w.cos();
+            im += s as f64 * w.sin();
+        }
+        2.0 * (re * re + im * im).sqrt() / signal.len() as f64
+    }
+
+    fn rms(signal: &[i16]) -> f64 {
+        let sum: f64 = signal.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        (sum / signal.len() as f64).sqrt()
+    }
+
+    fn db(x: f64) -> f64 {
+        20.0 * x.log10()
+    }
+
+    fn passband_ripple_db(in_rate: u32, out_rate: u32, freqs: &[f64]) -> f64 {
+        let gains: Vec<f64> = freqs
+            .iter()
+            .map(|&f| {
+                let mut rs = PolyphaseResampler::new(in_rate, out_rate);
+                let out = rs.process(&sine(f, in_rate, 1.0));
+                db(tone_amplitude(settled(&out), f, out_rate) / AMPLITUDE)
+            })
+            .collect();
+
+        let max = gains.iter().cloned().fold(f64::MIN, f64::max);
+        let min = gains.iter().cloned().fold(f64::MAX, f64::min);
+        max - min
+    }
+
+    fn stopband_level_db(in_rate: u32, out_rate: u32, freq: f64) -> f64 {
+        let mut rs = PolyphaseResampler::new(in_rate, out_rate);
+        let out = rs.process(&sine(freq, in_rate, 1.0));
+        db(rms(settled(&out)) * 2f64.sqrt() / AMPLITUDE)
+    }
+
+    #[test]
+    fn reduces_ratio() {
+        for (in_rate, out_rate, up, down) in [
+            (48_000, 16_000, 1, 3),
+            (44_100, 16_000, 160, 441),
+            (8_000, 16_000, 2, 1),
+        ] {
+            let rs = PolyphaseResampler::new(in_rate, out_rate);
+            assert_eq!((rs.up, rs.down), (up, down));
+        }
+    }
+
+    #[test]
+    fn same_rate_passes_through() {
+        let mut rs = PolyphaseResampler::new(16_000, 16_000);
+        let input = sine(440.0, 16_000, 0.1);
+        assert_eq!(rs.process(&input), input);
+    }
+
+    #[test]
+    fn output_length_follows_ratio() {
+        for (in_rate, out_rate) in [(48_000, 16_000), (44_100, 16_000), (22_050, 16_000)] {
+            let mut rs = PolyphaseResampler::new(in_rate, out_rate);
+            let mut total = 0;
+            for _ in 0..10 {
+                total += rs.process(&vec![0; in_rate as usize / 10]).len();
+            }
+            let diff = (total as i64 - out_rate as i64).abs();
+            let latency = rs.half_taps as i64 * out_rate as i64 / in_rate as i64 + 1;
+            assert!(diff <= latency, "{in_rate}->{out_rate}: got {total}");
+        }
+    }
+
+    #[test]
+    fn chunking_does_not_change_output() {
+        let input = sine(1_000.0, 44_100, 0.5);
+
+        let mut whole = PolyphaseResampler::new(44_100, 16_000);
+        let expected = whole.process(&input);
+
+        let mut chunked = PolyphaseResampler::new(44_100, 16_000);
+        let mut actual = Vec::new();
+        for (i, chunk) in input.chunks(1 + 37 * 3).enumerate() {
+            if i % 3 == 0 {
+                actual.extend(chunked.process(&[]));
+            }
+            actual.extend(chunked.process(chunk));
+        }
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn passband_ripple_48k() {
+        let freqs = [
+            100.0, 300.0, 1_000.0, 2_000.0, 3_000.0, 4_000.0, 5_000.0, 6_000.0,
+        ];
+        let ripple = passband_ripple_db(48_000, 16_000, &freqs);
+        assert!(ripple < 0.1, "ripple {ripple:.3} dB");
+    }
+
+    #[test]
+    fn passband_ripple_44k() {
+        let freqs = [100.0, 500.0, 1_500.0, 3_000.0, 4_500.0, 6_000.0];
+        let ripple = passband_ripple_db(44_100, 16_000, &freqs);
+        assert!(ripple < 0.1, "ripple {ripple:.3} dB");
+    }
+
+    #[test]
+    fn passband_ripple_upsampling() {
+        let freqs = [100.0, 1_000.0, 2_000.0, 3_000.0];
+        let ripple = passband_ripple_db(8_000, 16_000, &freqs);
+        assert!(ripple < 0.1, "ripple {ripple:.3} dB");
+    }
+
+    #[test]
+    fn stopband_rejection_48k() {
+        for freq in [8_500.0, 10_000.0, 12_000.0, 15_000.0, 20_000.0] {
+            let level = stopband_level_db(48_000, 16_000, freq);
+            assert!(level < -70.0, "{freq} Hz leaked at {level:.1} dB");
+        }
+    }
+
+    #[test]
+    fn stopband_rejection_44k() {
+        for freq in [8_500.0, 11_025.0, 16_000.0, 21_000.0] {
+            let level = stopband_level_db(44_100, 16_000, freq);
+            assert!(level < -70.0, "{freq} Hz leaked at {level:.1} dB");
+        }
+    }
+
+    #[test]
+    fn large_ratio_matches_table_quality() {
+        let mut rs = PolyphaseResampler::new(44_100, 16_001);
+        assert!(rs.phases.is_empty());
+
+        let out = rs.process(&sine(1_000.0, 44_100, 1.0));
+        let gain = db(tone_amplitude(settled(&out), 1_000.0, 16_001) / AMPLITUDE);
+        assert!(gain.abs() < 0.1, "gain {gain:.3} dB");
+
+        let level = stopband_level_db(44_100, 16_001, 12_000.0);
+        assert!(level < -70.0, "leaked at {level:.1} dB");
+    }
 }
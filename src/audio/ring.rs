@@ -0,0 +1,191 @@
+use rtrb::RingBuffer;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+#[derive(Debug, Default)]
+pub struct CaptureStats {
+    captured: AtomicU64,
+    dropped: AtomicU64,
+    overruns: AtomicU64,
+}
+
+impl CaptureStats {
+    pub fn captured(&self) -> u64 {
+        self.captured.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn overruns(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyStats {
+    pub last_ms: f32,
+    pub max_ms: f32,
+    total_ms: f64,
+    reads: u64,
+}
+
+impl LatencyStats {
+    fn record(&mut self, ms: f32) {
+        self.last_ms = ms;
+        self.max_ms = self.max_ms.max(ms);
+        self.total_ms += ms as f64;
+        self.reads += 1;
+    }
+
+    pub fn mean_ms(&self) -> f32 {
+        if self.reads == 0 {
+            0.0
+        } else {
+            (self.total_ms / self.reads as f64) as f32
+        }
+    }
+}
+
+pub fn audio_ring(capacity: usize, sample_rate: u32) -> (AudioWriter, AudioReader) {
+    let (producer, consumer) = RingBuffer::new(capacity);
+    let stats = Arc::new(CaptureStats::default());
+
+    (
+        AudioWriter {
+            producer,
+            stats: stats.clone(),
+        },
+        AudioReader {
+            consumer,
+            stats,
+            sample_rate,
+            latency: LatencyStats::default(),
+            reported_dropped: 0,
+        },
+    )
+}
+
+// Lives in the audio callback: never blocks and never allocates.
+pub struct AudioWriter {
+    producer: rtrb::Producer<i16>,
+    stats: Arc<CaptureStats>,
+}
+
+impl AudioWriter {
+    pub fn push(&mut self, samples: &[i16]) -> usize {
+        let written = samples.len().min(self.producer.slots());
+        if let Ok(chunk) = self.producer.write_chunk_uninit(written) {
+            chunk.fill_from_iter(samples[..written].iter().copied());
+        }
+
+        let dropped = (samples.len() - written) as u64;
+        self.stats
+            .captured
+            .fetch_add(samples.len() as u64, Ordering::Relaxed);
+        if dropped > 0 {
+            self.stats.dropped.fetch_add(dropped, Ordering::Relaxed);
+            self.stats.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+        written
+    }
+}
+
+pub struct AudioReader {
+    consumer: rtrb::Consumer<i16>,
+    stats: Arc<CaptureStats>,
+    sample_rate: u32,
+    latency: LatencyStats,
+    reported_dropped: u64,
+}
+
+impl AudioReader {
+    pub fn read(&mut self, max: usize) -> Vec<i16> {
+        let queued = self.consumer.slots();
+        if queued == 0 {
+            return Vec::new();
+        }
+        self.latency.record(self.samples_to_ms(queued));
+
+        match self.consumer.read_chunk(queued.min(max)) {
+            Ok(chunk) => chunk.into_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn stats(&self) -> &CaptureStats {
+        &self.stats
+    }
+
+    pub fn latency(&self) -> &LatencyStats {
+        &self.latency
+    }
+
+    pub fn take_dropped(&mut self) -> u64 {
+        let dropped = self.stats.dropped();
+        let new = dropped - self.reported_dropped;
+        self.reported_dropped = dropped;
+        new
+    }
+
+    pub fn samples_to_ms(&self, samples: usize) -> f32 {
+        samples as f32 * 1000.0 / self.sample_rate as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_pass_through_in_order() {
+        let (mut writer, mut reader) = audio_ring(8, 1_000);
+        assert_eq!(writer.push(&[1, 2, 3]), 3);
+        assert_eq!(writer.push(&[4, 5]), 2);
+        assert_eq!(reader.read(100), vec![1, 2, 3, 4, 5]);
+        assert!(reader.read(100).is_empty());
+    }
+
+    #[test]
+    fn read_is_limited_and_wraps_around() {
+        let (mut writer, mut reader) = audio_ring(4, 1_000);
+        writer.push(&[1, 2, 3]);
+        assert_eq!(reader.read(2), vec![1, 2]);
+        writer.push(&[4, 5, 6]);
+        assert_eq!(reader.read(10), vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn overrun_drops_newest_and_is_counted() {
+        let (mut writer, mut reader) = audio_ring(4, 1_000);
+        assert_eq!(writer.push(&[1, 2, 3]), 3);
+        assert_eq!(writer.push(&[4, 5, 6]), 1);
+        assert_eq!(writer.push(&[7]), 0);
+
+        assert_eq!(reader.stats().captured(), 7);
+        assert_eq!(reader.stats().dropped(), 3);
+        assert_eq!(reader.stats().overruns(), 2);
+        assert_eq!(reader.read(10), vec![1, 2, 3, 4]);
+
+        assert_eq!(reader.take_dropped(), 3);
+        assert_eq!(reader.take_dropped(), 0);
+    }
+
+    #[test]
+    fn latency_follows_queue_depth() {
+        let (mut writer, mut reader) = audio_ring(1_000, 1_000);
+        writer.push(&[0; 200]);
+        reader.read(1_000);
+        writer.push(&[0; 50]);
+        reader.read(1_000);
+        reader.read(1_000);
+
+        let latency = reader.latency();
+        assert_eq!(latency.last_ms, 50.0);
+        assert_eq!(latency.max_ms, 200.0);
+        assert_eq!(latency.mean_ms(), 125.0);
+    }
+}
@@ -0,0 +1,336 @@
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    capture, device,
+    downmix::ChannelStrategy,
+    ring::{self, AudioReader},
+    AudioSource, Chunk,
+};
+
+const DATA_TIMEOUT: Duration = Duration::from_secs(3);
+const SILENCE_TIMEOUT: Duration = Duration::from_secs(15);
+const BACKOFF_MIN: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const RING_SECS: u32 = 2;
+const CHUNK_MS: u32 = 100;
+
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    min: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            next: min,
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.next = self.min;
+    }
+}
+
+pub struct CaptureSupervisor {
+    host: cpal::Host,
+    wanted_device: String,
+    fuzzy_threshold: f64,
+    strategy: ChannelStrategy,
+    errors_tx: mpsc::Sender<String>,
+    errors_rx: mpsc::Receiver<String>,
+    stream: Option<cpal::Stream>,
+    reader: Option<AudioReader>,
+    sample_rate: u32,
+    device_name: String,
+    backoff: Backoff,
+    failures: usize,
+    retry_at: Option<Instant>,
+    last_sound: Instant,
+    last_data: Instant,
+}
+
+impl CaptureSupervisor {
+    pub fn start(
+        host: cpal::Host,
+        wanted_device: String,
+        fuzzy_threshold: f64,
+        strategy: ChannelStrategy,
+    ) -> Result<Self> {
+        let (errors_tx, errors_rx) = mpsc::channel();
+        let mut supervisor = Self {
+            host,
+            wanted_device,
+            fuzzy_threshold,
+            strategy,
+            errors_tx,
+            errors_rx,
+            stream: None,
+            reader: None,
+            sample_rate: 0,
+            device_name: String::new(),
+            backoff: Backoff::new(BACKOFF_MIN, BACKOFF_MAX),
+            failures: 0,
+            retry_at: None,
+            last_sound: Instant::now(),
+            last_data: Instant::now(),
+        };
+        supervisor.open()?;
+        Ok(supervisor)
+    }
+
+    // Returns None when no audio arrived before the timeout, so callers keep
+    // running their timers while the stream is stalled or reconnecting.
+    pub fn recv(&mut self, timeout: Duration) -> Option<Vec<i16>> {
+        let max_chunk = (self.sample_rate * CHUNK_MS / 1000) as usize;
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let Some(reader) = self.reader.as_mut().filter(|_| self.stream.is_some()) else {
+                let now = Instant::now();
+                match self.retry_at {
+                    Some(at) if at > now => {
+                        if now >= deadline {
+                            return None;
+                        }
+                        thread::sleep(at.min(deadline) - now);
+                    }
+                    _ => self.reconnect(),
+                }
+                continue;
+            };
+
+            if let Ok(err) = self.errors_rx.try_recv() {
+                self.fail(&err);
+                continue;
+            }
+
+            let chunk = reader.read(max_chunk);
+            if chunk.is_empty() {
+                if self.last_data.elapsed() >= DATA_TIMEOUT {
+                    self.fail(&format!("no audio for {}s", DATA_TIMEOUT.as_secs()));
+                } else if Instant::now() >= deadline {
+                    return None;
+                } else {
+                    thread::sleep(POLL_INTERVAL);
+                }
+                continue;
+            }
+            self.last_data = Instant::now();
+            self.report_overrun();
+
+            if chunk.iter().any(|&s| s != 0) {
+                self.last_sound = Instant::now();
+                self.backoff.reset();
+                self.failures = 0;
+            } else if self.last_sound.elapsed() >= SILENCE_TIMEOUT {
+                self.fail(&format!(
+                    "only digital silence for {}s",
+                    SILENCE_TIMEOUT.as_secs()
+                ));
+                continue;
+            }
+            return Some(chunk);
+        }
+    }
+
+    fn report_overrun(&mut self) {
+        let Some(reader) = self.reader.as_mut() else {
+            return;
+        };
+        let dropped = reader.take_dropped();
+        if dropped == 0 {
+            return;
+        }
+
+        let stats = reader.stats();
+        let latency = reader.latency();
+        println!(
+            "{}",
+            format!(
+                "[!] Audio overrun: dropped {:.0} ms ({} overruns, {:.2}% of captured audio), queue latency mean {:.0} ms / max {:.0} ms",
+                reader.samples_to_ms(dropped as usize),
+                stats.overruns(),
+                stats.dropped() as f64 * 100.0 / stats.captured().max(1) as f64,
+                latency.mean_ms(),
+                latency.max_ms,
+            )
+            .yellow()
+        );
+    }
+
+    fn fail(&mut self, reason: &str) {
+        println!("{}", format!("[!] Audio stream lost: {reason}").yellow());
+        self.stream = None;
+        self.reader = None;
+        self.failures += 1;
+        self.schedule_retry();
+    }
+
+    fn schedule_retry(&mut self) {
+        let delay = self.backoff.next_delay();
+        println!(
+            "{}",
+            format!("[*] Reconnecting audio in {:.1}s...", delay.as_secs_f32()).cyan()
+        );
+        self.retry_at = Some(Instant::now() + delay);
+    }
+
+    fn reconnect(&mut self) {
+        self.retry_at = None;
+        match self.open() {
+            Ok(()) => println!("{}", "[+] Audio stream restored".green().bold()),
+            Err(err) => {
+                println!("{}", format!("[!] {err:#}").yellow());
+                self.schedule_retry();
+            }
+        }
+    }
+
+    fn open(&mut self) -> Result<()> {
+        self.stream = None;
+        self.reader = None;
+        while self.errors_rx.try_recv().is_ok() {}
+
+        // Retry the same device once, then rotate through the fallbacks.
+        let mut candidates = self.candidates();
+        if !candidates.is_empty() {
+            let skip = self.failures.saturating_sub(1) % candidates.len();
+            candidates.rotate_left(skip);
+        }
+
+        let mut last_err = anyhow!("No input device found");
+        for device in candidates {
+            match self.open_device(&device) {
+                Ok(()) => {
+                    self.last_sound = Instant::now();
+                    self.last_data = Instant::now();
+                    return Ok(());
+                }
+                Err(err) => last_err = err,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    fn candidates(&self) -> Vec<cpal::Device> {
+        let mut devices = Vec::new();
+        if let Ok(device) =
+            device::select_input_device(&self.host, &self.wanted_device, self.fuzzy_threshold)
+        {
+            devices.push(device);
+        }
+        devices.extend(self.host.default_input_device());
+        if let Ok(all) = self.host.input_devices() {
+            devices.extend(all);
+        }
+
+        let mut seen = Vec::new();
+        devices.retain(|d| {
+            let name = d.description().map(|d| d.to_string()).ok();
+            if seen.contains(&name) {
+                false
+            } else {
+                seen.push(name);
+                true
+            }
+        });
+        devices
+    }
+
+    fn open_device(&mut self, device: &cpal::Device) -> Result<()> {
+        let supported = device.default_input_config()?;
+        let config = supported.config();
+
+        let (writer, reader) = ring::audio_ring(
+            (config.sample_rate * RING_SECS) as usize,
+            config.sample_rate,
+        );
+        let stream = capture::build_input_stream(
+            device,
+            &config,
+            supported.sample_format(),
+            self.strategy.clone(),
+            writer,
+            self.errors_tx.clone(),
+        )?;
+        stream.play().context("Unable to start the input stream")?;
+
+        let device_name = device.description()?.to_string();
+        println!(
+            "{}{}",
+            "[*] Input device: ".magenta().bold(),
+            device_name.magenta()
+        );
+        println!();
+        println!(
+            "{}\n{}\n{}\n{}",
+            "[*] Format:".bold().magenta(),
+            format!("    - channels={}", config.channels).magenta(),
+            format!("    - sample_rate={}", config.sample_rate).magenta(),
+            format!("    - sample_format={:?}", supported.sample_format()).magenta(),
+        );
+
+        self.sample_rate = config.sample_rate;
+        self.device_name = device_name;
+        self.stream = Some(stream);
+        self.reader = Some(reader);
+        Ok(())
+    }
+}
+
+impl AudioSource for CaptureSupervisor {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn device_name(&self) -> String {
+        self.device_name.clone()
+    }
+
+    fn next_chunk(&mut self, timeout: Duration) -> Chunk {
+        match self.recv(timeout) {
+            Some(samples) => Chunk::Audio(samples),
+            None => Chunk::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_until_max() {
+        let mut backoff = Backoff::new(Duration::from_millis(500), Duration::from_secs(3));
+        let delays: Vec<u128> = (0..5).map(|_| backoff.next_delay().as_millis()).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 3000, 3000]);
+    }
+
+    #[test]
+    fn backoff_reset_starts_over() {
+        let mut backoff = Backoff::new(Duration::from_millis(500), Duration::from_secs(30));
+        backoff.next_delay();
+        backoff.next_delay();
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result};
+use std::{path::Path, time::Duration};
+
+use super::{
+    downmix::{ChannelStrategy, Downmixer},
+    AudioSource, Chunk,
+};
+
+const CHUNK_MS: usize = 100;
+const TRAILING_SILENCE_MS: usize = 1500;
+
+pub struct WavSource {
+    name: String,
+    samples: Vec<i16>,
+    sample_rate: u32,
+    chunk_len: usize,
+    pos: usize,
+}
+
+impl WavSource {
+    pub fn open(path: impl AsRef<Path>, strategy: ChannelStrategy) -> Result<Self> {
+        let path = path.as_ref();
+        let reader = hound::WavReader::open(path)
+            .with_context(|| format!("Unable to open WAV file {}", path.display()))?;
+        let spec = reader.spec();
+
+        let interleaved: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|s| s.map(|s| s as f32 / scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        let mut samples = Downmixer::new(strategy, spec.channels as usize, spec.sample_rate)
+            .process(&interleaved);
+
+        // Give the VAD and the recognizer time to close the last utterance.
+        let rate = spec.sample_rate as usize;
+        samples.resize(samples.len() + rate * TRAILING_SILENCE_MS / 1000, 0);
+
+        Ok(Self {
+            name: path.display().to_string(),
+            samples,
+            sample_rate: spec.sample_rate,
+            chunk_len: (rate * CHUNK_MS / 1000).max(1),
+            pos: 0,
+        })
+    }
+}
+
+impl AudioSource for WavSource {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn device_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn next_chunk(&mut self, _timeout: Duration) -> Chunk {
+        if self.pos >= self.samples.len() {
+            return Chunk::End;
+        }
+        let end = (self.pos + self.chunk_len).min(self.samples.len());
+        let chunk = self.samples[self.pos..end].to_vec();
+        self.pos = end;
+        Chunk::Audio(chunk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+    use std::path::PathBuf;
+
+    fn temp_wav(
+        name: &str,
+        spec: hound::WavSpec,
+        write: impl FnOnce(&mut hound::WavWriter<std::io::BufWriter<std::fs::File>>),
+    ) -> PathBuf {
+        let path = temp_path(&format!("{name}.wav"));
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        write(&mut writer);
+        writer.finalize().unwrap();
+        path
+    }
+
+    fn next(source: &mut WavSource) -> Option<Vec<i16>> {
+        match source.next_chunk(Duration::ZERO) {
+            Chunk::Audio(chunk) => Some(chunk),
+            chunk => {
+                assert_eq!(chunk, Chunk::End);
+                None
+            }
+        }
+    }
+
+    fn read_all(source: &mut WavSource) -> Vec<i16> {
+        let mut out = Vec::new();
+        while let Some(chunk) = next(source) {
+            out.extend(chunk);
+        }
+        out
+    }
+
+    fn trailing(rate: usize) -> usize {
+        rate * TRAILING_SILENCE_MS / 1000
+    }
+
+    #[test]
+    fn reads_mono_i16() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let path = temp_wav("mono16", spec, |w| {
+            for s in [0i16, 1000, -1000, i16::MAX] {
+                w.write_sample(s).unwrap();
+            }
+        });
+
+        let mut source = WavSource::open(&path, ChannelStrategy::Average).unwrap();
+        assert_eq!(source.sample_rate(), 16_000);
+        let samples = read_all(&mut source);
+        assert_eq!(&samples[..4], &[0, 1000, -1000, i16::MAX]);
+        assert_eq!(samples.len(), 4 + trailing(16_000));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn downmixes_stereo_i24() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44_100,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let path = temp_wav("stereo24", spec, |w| {
+            for s in [1 << 22, 0, -(1 << 22), -(1 << 22)] {
+                w.write_sample(s).unwrap();
+            }
+        });
+
+        let mut source = WavSource::open(&path, ChannelStrategy::Average).unwrap();
+        assert_eq!(source.sample_rate(), 44_100);
+        let samples = read_all(&mut source);
+        assert_eq!(&samples[..2], &[8192, -16384]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reads_float_and_8bit() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let path = temp_wav("float", spec, |w| {
+            for s in [0.5f32, -0.25] {
+                w.write_sample(s).unwrap();
+            }
+        });
+        let samples = read_all(&mut WavSource::open(&path, ChannelStrategy::Average).unwrap());
+        assert_eq!(&samples[..2], &[16384, -8192]);
+        std::fs::remove_file(path).unwrap();
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 8,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let path = temp_wav("pcm8", spec, |w| {
+            for s in [64i8, -64] {
+                w.write_sample(s).unwrap();
+            }
+        });
+        let samples = read_all(&mut WavSource::open(&path, ChannelStrategy::Average).unwrap());
+        assert_eq!(&samples[..2], &[16384, -16384]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn yields_fixed_size_chunks() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let path = temp_wav("chunks", spec, |w| {
+            for _ in 0..2_000 {
+                w.write_sample(1i16).unwrap();
+            }
+        });
+
+        let mut source = WavSource::open(&path, ChannelStrategy::Average).unwrap();
+        let lens: Vec<usize> = std::iter::from_fn(|| next(&mut source))
+            .map(|c| c.len())
+            .collect();
+        assert!(lens[..lens.len() - 1].iter().all(|&l| l == 1_600));
+        assert_eq!(lens.iter().sum::<usize>(), 2_000 + trailing(16_000));
+        assert_eq!(source.next_chunk(Duration::ZERO), Chunk::End);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(WavSource::open("/nonexistent/aurora.wav", ChannelStrategy::Average).is_err());
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
 };
 
 use super::{has_any, App, Command};
+use crate::speech::language::Language;
 
 use enigo::{Enigo, Keyboard, Settings};
 
@@ -38,7 +39,12 @@ impl Runner for SystemRunner {
     }
 }
 
-pub fn execute_with<R: Runner>(runner: &mut R, cmd: Command) -> CommandResult {
+// The language of the utterance picks the locale of anything user-facing.
+pub fn execute_with<R: Runner>(
+    runner: &mut R,
+    cmd: Command,
+    language: Option<Language>,
+) -> CommandResult {
     match cmd {
         Command::Dictate(text) => dictate(text.as_str()),
         Command::SwitchWorkspace(workspace) => switch_workspace(runner, workspace),
@@ -48,9 +54,11 @@ pub fn execute_with<R: Runner>(runner: &mut R, cmd: Command) -> CommandResult {
         Command::AudioPause => audio_pause(runner),
         Command::AudioNext => audio_next(runner),
         Command::AudioPrevious => audio_previous(runner),
-        Command::FindInInternet(prompt) => find_in_internet(runner, &prompt),
+        Command::FindInInternet(prompt) => find_in_internet(runner, &prompt, language),
         Command::EndConversation => CommandResult::EndConversation,
         Command::Screenshot => screenshot(runner),
+        Command::Calibrate => CommandResult::Calibrate,
+        Command::SwitchLanguage(language) => CommandResult::SwitchLanguage(language),
         Command::BrightnessDown => set_brightness(runner, "10%-"),
         Command::BrightnessUp => set_brightness(runner, "10%+"),
         Command::BrightnessMax => set_brightness(runner, "100%"),
@@ -241,11 +249,17 @@ fn sleep<R: Runner>(runner: &mut R) -> CommandResult {
     CommandResult::Running
 }
 
-fn find_in_internet<R: Runner>(runner: &mut R, prompt: &String) -> CommandResult {
-    runner.spawn(
-        "xdg-open",
-        &[format!("https://www.google.com/search?q={}", prompt).as_str()],
-    );
+fn find_in_internet<R: Runner>(
+    runner: &mut R,
+    prompt: &String,
+    language: Option<Language>,
+) -> CommandResult {
+    let mut url = format!("https://www.google.com/search?q={}", prompt);
+    if let Some(language) = language {
+        url.push_str("&hl=");
+        url.push_str(language.code());
+    }
+    runner.spawn("xdg-open", &[url.as_str()]);
     CommandResult::Running
 }
 
@@ -280,9 +294,9 @@ fn audio_previous<R: Runner>(runner: &mut R) -> CommandResult {
     CommandResult::Running
 }
 
-pub fn execute(cmd: Command) -> CommandResult {
+pub fn execute(cmd: Command, language: Option<Language>) -> CommandResult {
     let mut r = SystemRunner;
-    execute_with(&mut r, cmd)
+    execute_with(&mut r, cmd, language)
 }
 
 #[cfg(test)]
@@ -353,7 +367,7 @@ mod tests {
     #[test]
     fn execute_open_firefox_spawns_firefox() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Firefox));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Firefox), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "firefox");
@@ -362,7 +376,7 @@ mod tests {
     #[test]
     fn execute_open_dolphin_spawns_dolphin() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Dolphin));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Dolphin), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "dolphin");
@@ -371,7 +385,7 @@ mod tests {
     #[test]
     fn execute_open_telegram_spawns_telegram() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "Telegram");
@@ -383,7 +397,7 @@ mod tests {
             fail_telegram: true,
             ..Default::default()
         };
-        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram), None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 2);
@@ -398,7 +412,7 @@ mod tests {
             fail_telegram_desktop: true,
             ..Default::default()
         };
-        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Telegram), None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 3);
@@ -411,7 +425,7 @@ mod tests {
     #[test]
     fn execute_volume_up_calls_wpctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::VolumeUp);
+        let keep = execute_with(&mut r, Command::VolumeUp, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -425,7 +439,7 @@ mod tests {
     #[test]
     fn execute_quit_stops() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Quit);
+        let keep = execute_with(&mut r, Command::Quit, None);
         assert_eq!(keep, CommandResult::Quit);
         assert!(r.calls.is_empty());
     }
@@ -433,7 +447,7 @@ mod tests {
     #[test]
     fn execute_open_obsidian_spawns_obsidian() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Obsidian));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Obsidian), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "obsidian");
@@ -445,7 +459,7 @@ mod tests {
             fail_obsidian: true,
             ..Default::default()
         };
-        let keep = execute_with(&mut r, Command::OpenApp(App::Obsidian));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Obsidian), None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 2);
@@ -457,7 +471,7 @@ mod tests {
     #[test]
     fn execute_open_steam_spawns_steam() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Steam));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Steam), None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -470,7 +484,7 @@ mod tests {
             fail_steam: true,
             ..Default::default()
         };
-        let keep = execute_with(&mut r, Command::OpenApp(App::Steam));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Steam), None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 2);
@@ -482,7 +496,7 @@ mod tests {
     #[test]
     fn execute_audio_pause_calls_playerctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::AudioPause);
+        let keep = execute_with(&mut r, Command::AudioPause, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -493,7 +507,7 @@ mod tests {
     #[test]
     fn execute_audio_next_calls_playerctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::AudioNext);
+        let keep = execute_with(&mut r, Command::AudioNext, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -504,7 +518,7 @@ mod tests {
     #[test]
     fn execute_audio_previous_calls_playerctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::AudioPrevious);
+        let keep = execute_with(&mut r, Command::AudioPrevious, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls[1].0, "playerctl");
@@ -514,7 +528,7 @@ mod tests {
     #[test]
     fn execute_audio_max_calls_wpctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::VolumeMax);
+        let keep = execute_with(&mut r, Command::VolumeMax, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -528,7 +542,7 @@ mod tests {
     #[test]
     fn execute_brightness_max_calls_brightnessctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::BrightnessMax);
+        let keep = execute_with(&mut r, Command::BrightnessMax, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -539,7 +553,7 @@ mod tests {
     #[test]
     fn execute_brightness_min_calls_brightnessctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::BrightnessMin);
+        let keep = execute_with(&mut r, Command::BrightnessMin, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -550,7 +564,7 @@ mod tests {
     #[test]
     fn execute_brightness_up_calls_brightnessctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::BrightnessUp);
+        let keep = execute_with(&mut r, Command::BrightnessUp, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -561,7 +575,7 @@ mod tests {
     #[test]
     fn execute_brightness_down_calls_brightnessctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::BrightnessDown);
+        let keep = execute_with(&mut r, Command::BrightnessDown, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -572,7 +586,7 @@ mod tests {
     #[test]
     fn execute_poweroff_calls_shutdown() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Poweroff);
+        let keep = execute_with(&mut r, Command::Poweroff, None);
         assert_eq!(keep, CommandResult::Quit);
 
         assert_eq!(r.calls.len(), 1);
@@ -582,7 +596,7 @@ mod tests {
     #[test]
     fn execute_reboot_calls_reboot() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Reboot);
+        let keep = execute_with(&mut r, Command::Reboot, None);
         assert_eq!(keep, CommandResult::Quit);
 
         assert_eq!(r.calls.len(), 1);
@@ -592,7 +606,7 @@ mod tests {
     #[test]
     fn execute_sleep_calls_systemctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Sleep);
+        let keep = execute_with(&mut r, Command::Sleep, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -608,7 +622,8 @@ mod tests {
                 SystemToggles::Wifi => {
                     for _ in 0..=10 {
                         let mut r = FakeRunner::default();
-                        let keep = execute_with(&mut r, Command::SystemToggle(SystemToggles::Wifi));
+                        let keep =
+                            execute_with(&mut r, Command::SystemToggle(SystemToggles::Wifi), None);
 
                         assert_eq!(keep, CommandResult::Running);
 
@@ -620,8 +635,11 @@ mod tests {
                     }
                 }
                 SystemToggles::Bluetooth => {
-                    let keep =
-                        execute_with(&mut r, Command::SystemToggle(SystemToggles::Bluetooth));
+                    let keep = execute_with(
+                        &mut r,
+                        Command::SystemToggle(SystemToggles::Bluetooth),
+                        None,
+                    );
 
                     assert_eq!(keep, CommandResult::Running);
 
@@ -631,7 +649,8 @@ mod tests {
                     assert_eq!(r.calls[1].1[0], "power");
                 }
                 SystemToggles::Volume => {
-                    let keep = execute_with(&mut r, Command::SystemToggle(SystemToggles::Volume));
+                    let keep =
+                        execute_with(&mut r, Command::SystemToggle(SystemToggles::Volume), None);
 
                     assert_eq!(keep, CommandResult::Running);
 
@@ -643,8 +662,11 @@ mod tests {
                     );
                 }
                 SystemToggles::DoNotDisturb => {
-                    let keep =
-                        execute_with(&mut r, Command::SystemToggle(SystemToggles::DoNotDisturb));
+                    let keep = execute_with(
+                        &mut r,
+                        Command::SystemToggle(SystemToggles::DoNotDisturb),
+                        None,
+                    );
 
                     assert_eq!(keep, CommandResult::Running);
 
@@ -664,8 +686,11 @@ mod tests {
                     let mut r = FakeRunner::default();
                     r.exec_output_values
                         .insert("xsct".to_string(), "6500".to_string());
-                    let keep =
-                        execute_with(&mut r, Command::SystemToggle(SystemToggles::NightLight));
+                    let keep = execute_with(
+                        &mut r,
+                        Command::SystemToggle(SystemToggles::NightLight),
+                        None,
+                    );
 
                     assert_eq!(keep, CommandResult::Running);
                     assert_eq!(r.calls.len(), 2);
@@ -679,8 +704,11 @@ mod tests {
                     let mut r = FakeRunner::default();
                     r.exec_output_values
                         .insert("xsct".to_string(), "4500".to_string());
-                    let keep =
-                        execute_with(&mut r, Command::SystemToggle(SystemToggles::NightLight));
+                    let keep = execute_with(
+                        &mut r,
+                        Command::SystemToggle(SystemToggles::NightLight),
+                        None,
+                    );
 
                     assert_eq!(keep, CommandResult::Running);
                     assert_eq!(r.calls.len(), 2);
@@ -696,7 +724,7 @@ mod tests {
     #[test]
     fn execute_open_terminal_spawns_kitty() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::OpenApp(App::Terminal));
+        let keep = execute_with(&mut r, Command::OpenApp(App::Terminal), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "kitty");
@@ -705,7 +733,7 @@ mod tests {
     #[test]
     fn execute_screenshot_spawns_spectacle() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Screenshot);
+        let keep = execute_with(&mut r, Command::Screenshot, None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "spectacle");
@@ -714,7 +742,7 @@ mod tests {
     #[test]
     fn execute_volume_down_calls_wpctl() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::VolumeDown);
+        let keep = execute_with(&mut r, Command::VolumeDown, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 1);
@@ -728,7 +756,7 @@ mod tests {
     #[test]
     fn execute_end_conversation_returns_end_conversation() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::EndConversation);
+        let keep = execute_with(&mut r, Command::EndConversation, None);
         assert_eq!(keep, CommandResult::EndConversation);
         assert!(r.calls.is_empty());
     }
@@ -736,7 +764,7 @@ mod tests {
     #[test]
     fn execute_unknown_returns_running() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::Unknown("test".to_string()));
+        let keep = execute_with(&mut r, Command::Unknown("test".to_string()), None);
         assert_eq!(keep, CommandResult::Running);
         assert!(r.calls.is_empty());
     }
@@ -744,7 +772,7 @@ mod tests {
     #[test]
     fn execute_audio_previous_calls_playerctl_twice() {
         let mut r = FakeRunner::default();
-        let keep = execute_with(&mut r, Command::AudioPrevious);
+        let keep = execute_with(&mut r, Command::AudioPrevious, None);
         assert_eq!(keep, CommandResult::Running);
 
         assert_eq!(r.calls.len(), 2);
@@ -758,7 +786,7 @@ mod tests {
     fn execute_find_in_internet_opens_browser() {
         let mut r = FakeRunner::default();
         let prompt = "rust programming".to_string();
-        let result = execute_with(&mut r, Command::FindInInternet(prompt.clone()));
+        let result = execute_with(&mut r, Command::FindInInternet(prompt.clone()), None);
         assert_eq!(result, CommandResult::Running);
         assert_eq!(r.calls.len(), 1);
         assert_eq!(r.calls[0].0, "xdg-open");
@@ -767,13 +795,33 @@ mod tests {
         assert!(r.calls[0].1[0].contains("programming"));
     }
 
+    #[test]
+    fn execute_find_in_internet_uses_the_spoken_language() {
+        let mut r = FakeRunner::default();
+        let cmd = Command::FindInInternet("погода".to_string());
+        execute_with(&mut r, cmd, Some(Language::Ukrainian));
+        assert!(r.calls[0].1[0].ends_with("&hl=uk"));
+
+        let mut r = FakeRunner::default();
+        execute_with(&mut r, Command::FindInInternet("weather".to_string()), None);
+        assert!(!r.calls[0].1[0].contains("&hl="));
+    }
+
+    #[test]
+    fn execute_switch_language_is_left_to_the_caller() {
+        let mut r = FakeRunner::default();
+        let result = execute_with(&mut r, Command::SwitchLanguage(Language::English), None);
+        assert_eq!(result, CommandResult::SwitchLanguage(Language::English));
+        assert!(r.calls.is_empty());
+    }
+
     #[test]
     fn execute_switch_workspace_switches_workspace() {
         let mut r = FakeRunner {
             enviroment: String::from("Hyprland"),
             ..Default::default()
         };
-        let keep = execute_with(&mut r, Command::SwitchWorkspace(7));
+        let keep = execute_with(&mut r, Command::SwitchWorkspace(7), None);
         assert_eq!(keep, CommandResult::Running);
         assert_eq!(r.calls.len(), 2);
         assert_eq!(
@@ -794,7 +842,7 @@ mod tests {
                 enviroment,
                 ..Default::default()
             };
-            let keep = execute_with(&mut r, Command::SwitchWorkspace(7));
+            let keep = execute_with(&mut r, Command::SwitchWorkspace(7), None);
             assert_eq!(keep, CommandResult::Running);
             assert_eq!(r.calls.len(), 2);
 
@@ -1,10 +1,10 @@
 pub mod executor;
 pub mod parser;
 
-use crate::{settings::manager::SettingsManager, SETTINGS_FILE_PATH};
+use crate::{settings::manager::SettingsManager, speech::language::Language, SETTINGS_FILE_PATH};
 use strsim::jaro_winkler;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SystemToggles {
     Volume,
     Wifi,
@@ -26,7 +26,7 @@ impl SystemToggles {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Command {
     OpenApp(App),
     OpenFolder(String),
@@ -47,13 +47,52 @@ pub enum Command {
     FindInInternet(String),
     EndConversation,
     Screenshot,
+    Calibrate,
+    SwitchLanguage(Language),
     SystemToggle(SystemToggles),
     SwitchWorkspace(u8),
     Quit,
     Unknown(String),
 }
 
-#[derive(Debug, Clone)]
+impl Command {
+    // Commands whose argument can be any phrase, beyond the parser's vocabulary.
+    pub fn is_free_form(&self) -> bool {
+        matches!(
+            self,
+            Command::Dictate(_)
+                | Command::FindInInternet(_)
+                | Command::OpenFolder(_)
+                | Command::SwitchWorkspace(_)
+        )
+    }
+
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Command::Poweroff | Command::Reboot | Command::Sleep | Command::Quit
+        )
+    }
+
+    // Slot-free commands that are safe to run before the utterance is final.
+    pub fn is_instant(&self) -> bool {
+        matches!(
+            self,
+            Command::AudioPause
+                | Command::AudioNext
+                | Command::AudioPrevious
+                | Command::VolumeUp
+                | Command::VolumeDown
+                | Command::VolumeMax
+                | Command::BrightnessUp
+                | Command::BrightnessDown
+                | Command::BrightnessMax
+                | Command::BrightnessMin
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum App {
     Firefox,
     Terminal,
@@ -67,6 +106,8 @@ pub enum App {
 pub enum CommandResult {
     Running,
     EndConversation,
+    Calibrate,
+    SwitchLanguage(Language),
     Quit,
 }
 
@@ -1,58 +1,257 @@
 use super::{has_any, App, Command, SystemToggles};
-use crate::normalizer::text::normalize;
+use crate::{normalizer::text::normalize, speech::language::Language};
+
+const QUIT: &[&str] = &[
+    "вихід",
+    "вимкнись",
+    "заверши роботу",
+    "стоп",
+    "stop",
+    "exit",
+    "quit",
+];
+const END_CONVERSATION: &[&str] = &[
+    "досить",
+    "все",
+    "закінчимо",
+    "that's all",
+    "that's it",
+    "nevermind",
+    "bye",
+];
+const SCREENSHOT: &[&str] = &[
+    "скріншот",
+    "знімок екрана",
+    "знімок екрану",
+    "capture screen",
+    "screenshot",
+];
+const CALIBRATE: &[&str] = &["калібрування", "калібруй", "calibrate", "calibration"];
+const SWITCH_LANGUAGE: &[&str] = &[
+    "перейди на",
+    "переключись на",
+    "switch to",
+    "change language to",
+];
+const ENGLISH: &[&str] = &["англійську", "англійська", "english"];
+const UKRAINIAN: &[&str] = &["українську", "українська", "ukrainian"];
+const COMPUTER: &[&str] = &["пк", "комп'ютер", "pc", "computer"];
+const POWEROFF: &[&str] = &["shutdown", "poweroff", "вимкни"];
+const REBOOT: &[&str] = &["restart", "reboot", "перезапусти"];
+const SLEEP: &[&str] = &["suspend", "sleep", "сон"];
+const SEARCH: &[&str] = &["знайди ", "пошук ", "шукай ", "find ", "search "];
+const WORKSPACE: &[&str] = &["робочий стіл", "workspace"];
+const DICTATE: &[&str] = &["диктую", "слухай", "listen", "dictate"];
+const MINIMUM: &[&str] = &["minimum", "мінімум"];
+const MAXIMUM: &[&str] = &["maximum", "максимум"];
+const VOLUME: &[&str] = &["гучність", "звук", "громкість", "sound", "volume"];
+const BRIGHTNESS: &[&str] = &["яркість", "яркість екрану", "brightness"];
+const UP: &[&str] = &[
+    "більше",
+    "плюс",
+    "вгору",
+    "підніми",
+    "додай",
+    "вище",
+    "up",
+    "higher",
+];
+const DOWN: &[&str] = &[
+    "менше",
+    "мінус",
+    "вниз",
+    "зменш",
+    "убав",
+    "нижче",
+    "down",
+    "lower",
+];
+const OPEN: &[&str] = &["відкрий", "запусти", "включи", "open", "launch"];
+const FIREFOX: &[&str] = &[
+    "firefox",
+    "файрфокс",
+    "браузер",
+    "ферфакс",
+    "фаєр фокус",
+    "фаєрфоксу",
+    "browser",
+    "internet browser",
+];
+const TERMINAL: &[&str] = &[
+    "термінал",
+    "консоль",
+    "командний рядок",
+    "kitty",
+    "terminal",
+];
+const OBSIDIAN: &[&str] = &["obsidian", "обсідіан", "нотатки", "notes"];
+const STEAM: &[&str] = &[
+    "ігри",
+    "ігровий лаунчер",
+    "steam",
+    "стім",
+    "games",
+    "game launcher",
+];
+const DOLPHIN: &[&str] = &[
+    "файли",
+    "файловий менеджер",
+    "дельфін",
+    "провідник",
+    "file explorer",
+    "dolphin",
+    "files",
+    "explorer",
+];
+const TELEGRAM: &[&str] = &["telegram", "месенджер", "телеграм", "messenger"];
+const FOLDER: &[&str] = &["папку", "folder"];
+const NEXT: &[&str] = &["наступний", "наступна", "наступне", "next"];
+const PREVIOUS: &[&str] = &[
+    "минула",
+    "минулий",
+    "минуле",
+    "минулі",
+    "минуло",
+    "previous",
+];
+const TOGGLE: &[&str] = &[
+    "увімкни",
+    "включи",
+    "enable",
+    "turn on",
+    "activate",
+    "вимкни",
+    "виключи",
+    "disable",
+    "turn off",
+    "deactivate",
+    "переключи",
+    "toggle",
+];
+const WIFI: &[&str] = &["wifi", "wi-fi", "вайфай", "бездротовий інтернет"];
+const BLUETOOTH: &[&str] = &["bluetooth", "блутуз", "блютуз", "бездротовий"];
+const NIGHT_LIGHT: &[&str] = &["нічний режим", "night light", "нічне світло"];
+const DND: &[&str] = &["не турбувати", "do not disturb", "dnd", "тихий режим"];
+const PAUSE: &[&str] = &[
+    "постав на паузу",
+    "пауза",
+    "віднови",
+    "зніми з паузи",
+    "play",
+    "pause",
+];
+const YES: &[&str] = &["так", "підтверджую", "авжеж", "yes", "yeah", "confirm"];
+const NO: &[&str] = &["ні", "скасуй", "no", "cancel"];
+
+const TABLES: &[&[&str]] = &[
+    QUIT,
+    END_CONVERSATION,
+    SCREENSHOT,
+    CALIBRATE,
+    SWITCH_LANGUAGE,
+    ENGLISH,
+    UKRAINIAN,
+    COMPUTER,
+    POWEROFF,
+    REBOOT,
+    SLEEP,
+    SEARCH,
+    WORKSPACE,
+    DICTATE,
+    MINIMUM,
+    MAXIMUM,
+    VOLUME,
+    BRIGHTNESS,
+    UP,
+    DOWN,
+    OPEN,
+    FIREFOX,
+    TERMINAL,
+    OBSIDIAN,
+    STEAM,
+    DOLPHIN,
+    TELEGRAM,
+    FOLDER,
+    NEXT,
+    PREVIOUS,
+    TOGGLE,
+    WIFI,
+    BLUETOOTH,
+    NIGHT_LIGHT,
+    DND,
+    PAUSE,
+    YES,
+    NO,
+];
+
+// Every phrase the parser reacts to, used to bias the recognizer.
+pub fn vocabulary() -> Vec<String> {
+    let mut words: Vec<String> = TABLES
+        .iter()
+        .flat_map(|table| table.iter())
+        .map(|phrase| phrase.trim().to_string())
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+pub fn parse_confirmation(raw: &str) -> Option<bool> {
+    let t = normalize(raw);
+    let words: Vec<&str> = t.split_whitespace().collect();
+
+    if words.iter().any(|w| YES.contains(w)) {
+        Some(true)
+    } else if words.iter().any(|w| NO.contains(w)) {
+        Some(false)
+    } else {
+        None
+    }
+}
 
 pub fn parse_command(raw: &str) -> Command {
     let t = normalize(raw);
 
-    if matches!(
-        t.as_str(),
-        "вихід" | "вимкнись" | "заверши роботу" | "стоп" | "stop" | "exit" | "quit"
-    ) {
+    if QUIT.contains(&t.as_str()) {
         return Command::Quit;
     }
 
-    if has_any(
-        &t,
-        &[
-            "досить",
-            "все",
-            "закінчимо",
-            "that's all",
-            "that's it",
-            "nevermind",
-            "bye",
-        ],
-    ) {
+    if has_any(&t, END_CONVERSATION) {
         return Command::EndConversation;
     }
 
-    if has_any(
-        &t,
-        &[
-            "скріншот",
-            "знімок екрана",
-            "знімок екрану",
-            "capture screen",
-            "screenshot",
-        ],
-    ) {
+    if has_any(&t, SCREENSHOT) {
         return Command::Screenshot;
     }
 
-    if has_any(&t, &["пк", "комп'ютер", "pc", "computer"]) {
-        if has_any(&t, &["shutdown", "poweroff", "вимкни"]) {
+    // Exact match only: "відкалібруй" is too close to "відкрий" for fuzzy matching.
+    if CALIBRATE.iter().any(|phrase| t.contains(phrase)) {
+        return Command::Calibrate;
+    }
+
+    if SWITCH_LANGUAGE.iter().any(|phrase| t.contains(phrase)) {
+        if has_any(&t, ENGLISH) {
+            return Command::SwitchLanguage(Language::English);
+        }
+        if has_any(&t, UKRAINIAN) {
+            return Command::SwitchLanguage(Language::Ukrainian);
+        }
+    }
+
+    if has_any(&t, COMPUTER) {
+        if has_any(&t, POWEROFF) {
             return Command::Poweroff;
         }
-        if has_any(&t, &["restart", "reboot", "перезапусти"]) {
+        if has_any(&t, REBOOT) {
             return Command::Reboot;
         }
-        if has_any(&t, &["suspend", "sleep", "сон"]) {
+        if has_any(&t, SLEEP) {
             return Command::Sleep;
         }
     }
 
-    if has_any(&t, &["знайди ", "пошук ", "шукай ", "find ", "search "]) {
-        for prefix in ["знайди ", "пошук ", "шукай ", "find ", "search "].iter() {
+    if has_any(&t, SEARCH) {
+        for prefix in SEARCH.iter() {
             if t.starts_with(prefix) {
                 let query = t.trim_start_matches(prefix);
                 return Command::FindInInternet(query.to_string());
@@ -60,7 +259,7 @@ pub fn parse_command(raw: &str) -> Command {
         }
     }
 
-    if has_any(&t, &["робочий стіл", "workspace"]) {
+    if has_any(&t, WORKSPACE) {
         if let Some(workspace) = t.strip_prefix("робочий стіл ") {
             return Command::SwitchWorkspace(workspace.parse().unwrap_or(0));
         }
@@ -68,8 +267,8 @@ pub fn parse_command(raw: &str) -> Command {
             return Command::SwitchWorkspace(workspace.parse().unwrap_or(0));
         }
     }
-    if has_any(&t, &["диктую", "слухай", "listen", "dictate"]) {
-        for prefix in ["диктую", "слухай", "listen", "dictate"].iter() {
+    if has_any(&t, DICTATE) {
+        for prefix in DICTATE.iter() {
             if t.starts_with(prefix) {
                 let query = t.trim_start_matches(&format!("{} ", prefix));
                 return Command::Dictate(query.to_string());
@@ -77,127 +276,60 @@ pub fn parse_command(raw: &str) -> Command {
         }
     }
 
-    if has_any(&t, &["minimum", "мінімум"]) {
-        if has_any(&t, &["яркість", "яркість екрану", "brightness"]) {
+    if has_any(&t, MINIMUM) {
+        if has_any(&t, BRIGHTNESS) {
             return Command::BrightnessMin;
         }
     }
 
-    if has_any(&t, &["maximum", "максимум"]) {
-        if has_any(&t, &["гучність", "звук", "громкість", "sound", "volume"]) {
+    if has_any(&t, MAXIMUM) {
+        if has_any(&t, VOLUME) {
             return Command::VolumeMax;
         }
-        if has_any(&t, &["яркість", "яркість екрану", "brightness"]) {
+        if has_any(&t, BRIGHTNESS) {
             return Command::BrightnessMax;
         }
     }
 
-    if has_any(
-        &t,
-        &[
-            "більше",
-            "плюс",
-            "вгору",
-            "підніми",
-            "додай",
-            "вище",
-            "up",
-            "higher",
-        ],
-    ) {
-        if has_any(&t, &["гучність", "звук", "громкість", "sound", "volume"]) {
+    if has_any(&t, UP) {
+        if has_any(&t, VOLUME) {
             return Command::VolumeUp;
         }
-        if has_any(&t, &["яркість", "яркість екрану", "brightness"]) {
+        if has_any(&t, BRIGHTNESS) {
             return Command::BrightnessUp;
         }
     }
-    if has_any(
-        &t,
-        &[
-            "менше",
-            "мінус",
-            "вниз",
-            "зменш",
-            "убав",
-            "нижче",
-            "down",
-            "lower",
-        ],
-    ) {
-        if has_any(&t, &["гучність", "звук", "громкість", "sound", "volume"]) {
+    if has_any(&t, DOWN) {
+        if has_any(&t, VOLUME) {
             return Command::VolumeDown;
         }
-        if has_any(&t, &["яркість", "яркість екрану", "brightness"]) {
+        if has_any(&t, BRIGHTNESS) {
             return Command::BrightnessDown;
         }
     }
 
-    if has_any(&t, &["відкрий", "запусти", "включи", "open", "launch"]) {
-        if has_any(
-            &t,
-            &[
-                "firefox",
-                "файрфокс",
-                "браузер",
-                "ферфакс",
-                "фаєр фокус",
-                "фаєрфоксу",
-                "browser",
-                "internet browser",
-            ],
-        ) {
+    if has_any(&t, OPEN) {
+        if has_any(&t, FIREFOX) {
             return Command::OpenApp(App::Firefox);
         }
-        if has_any(
-            &t,
-            &[
-                "термінал",
-                "консоль",
-                "командний рядок",
-                "kitty",
-                "terminal",
-            ],
-        ) {
+        if has_any(&t, TERMINAL) {
             return Command::OpenApp(App::Terminal);
         }
-        if has_any(&t, &["obsidian", "обсідіан", "нотатки", "notes"]) {
+        if has_any(&t, OBSIDIAN) {
             return Command::OpenApp(App::Obsidian);
         }
-        if has_any(
-            &t,
-            &[
-                "ігри",
-                "ігровий лаунчер",
-                "steam",
-                "стім",
-                "games",
-                "game launcher",
-            ],
-        ) {
+        if has_any(&t, STEAM) {
             return Command::OpenApp(App::Steam);
         }
-        if has_any(
-            &t,
-            &[
-                "файли",
-                "файловий менеджер",
-                "дельфін",
-                "провідник",
-                "file explorer",
-                "dolphin",
-                "files",
-                "explorer",
-            ],
-        ) {
+        if has_any(&t, DOLPHIN) {
             return Command::OpenApp(App::Dolphin);
         }
-        if has_any(&t, &["telegram", "месенджер", "телеграм", "messenger"]) {
+        if has_any(&t, TELEGRAM) {
             return Command::OpenApp(App::Telegram);
         }
-        if has_any(&t, &["папку", "folder"]) {
-            for prefix1 in ["відкрий", "запусти", "включи", "open", "launch"] {
-                for prefix2 in ["папку", "folder"] {
+        if has_any(&t, FOLDER) {
+            for prefix1 in OPEN {
+                for prefix2 in FOLDER {
                     let prefix = format!("{} {}", prefix1, prefix2);
                     if t.starts_with(&prefix) {
                         let folder = t.trim_start_matches(&prefix).trim().to_string();
@@ -208,71 +340,31 @@ pub fn parse_command(raw: &str) -> Command {
         }
     }
 
-    if has_any(&t, &["наступний", "наступна", "наступне", "next"]) {
+    if has_any(&t, NEXT) {
         return Command::AudioNext;
-    } else if has_any(
-        &t,
-        &[
-            "минула",
-            "минулий",
-            "минуле",
-            "минулі",
-            "минуло",
-            "previous",
-        ],
-    ) {
+    } else if has_any(&t, PREVIOUS) {
         return Command::AudioPrevious;
     }
 
-    if has_any(
-        &t,
-        &[
-            "увімкни",
-            "включи",
-            "enable",
-            "turn on",
-            "activate",
-            "вимкни",
-            "виключи",
-            "disable",
-            "turn off",
-            "deactivate",
-            "переключи",
-            "toggle",
-        ],
-    ) {
-        if has_any(&t, &["wifi", "wi-fi", "вайфай", "бездротовий інтернет"])
-        {
+    if has_any(&t, TOGGLE) {
+        if has_any(&t, WIFI) {
             return Command::SystemToggle(SystemToggles::Wifi);
         }
-        if has_any(&t, &["bluetooth", "блутуз", "блютуз", "бездротовий"]) {
+        if has_any(&t, BLUETOOTH) {
             return Command::SystemToggle(SystemToggles::Bluetooth);
         }
-        if has_any(&t, &["нічний режим", "night light", "нічне світло"]) {
+        if has_any(&t, NIGHT_LIGHT) {
             return Command::SystemToggle(SystemToggles::NightLight);
         }
-        if has_any(
-            &t,
-            &["не турбувати", "do not disturb", "dnd", "тихий режим"],
-        ) {
+        if has_any(&t, DND) {
             return Command::SystemToggle(SystemToggles::DoNotDisturb);
         }
-        if has_any(&t, &["гучність", "звук", "громкість", "sound", "volume"]) {
+        if has_any(&t, VOLUME) {
             return Command::SystemToggle(SystemToggles::Volume);
         }
     }
 
-    if has_any(
-        &t,
-        &[
-            "постав на паузу",
-            "пауза",
-            "віднови",
-            "зніми з паузи",
-            "play",
-            "pause",
-        ],
-    ) {
+    if has_any(&t, PAUSE) {
         return Command::AudioPause;
     }
 
@@ -359,6 +451,51 @@ mod tests {
         assert!(matches!(cmd, Command::AudioNext));
     }
 
+    #[test]
+    fn parse_switch_language() {
+        assert_eq!(
+            parse_command("switch to English"),
+            Command::SwitchLanguage(Language::English)
+        );
+        assert_eq!(
+            parse_command("перейди на українську"),
+            Command::SwitchLanguage(Language::Ukrainian)
+        );
+        assert_eq!(
+            parse_command("переключись на англійську"),
+            Command::SwitchLanguage(Language::English)
+        );
+    }
+
+    #[test]
+    fn parse_confirmation_answers() {
+        assert_eq!(parse_confirmation("Так!"), Some(true));
+        assert_eq!(parse_confirmation("yes please"), Some(true));
+        assert_eq!(parse_confirmation("ні, не треба"), Some(false));
+        assert_eq!(parse_confirmation("cancel"), Some(false));
+        assert_eq!(parse_confirmation("такси"), None);
+    }
+
+    #[test]
+    fn vocabulary_lists_parser_phrases() {
+        let words = vocabulary();
+        for phrase in ["обсідіан", "дельфін", "знайди", "do not disturb"] {
+            assert!(words.iter().any(|w| w == phrase), "missing {phrase}");
+        }
+        assert_eq!(words.iter().filter(|w| *w == "вимкни").count(), 1);
+    }
+
+    #[test]
+    fn parse_calibrate() {
+        for phrase in ["відкалібруй мікрофон", "calibrate microphone"] {
+            let cmd = parse_command(phrase);
+            assert!(
+                matches!(cmd, Command::Calibrate),
+                "failed for phrase: {phrase}"
+            );
+        }
+    }
+
     #[test]
     fn parse_audio_previous() {
         let cmd = parse_command("минула пісня");
@@ -1,168 +1,315 @@
+mod assistant;
 mod audio;
 mod commands;
 mod normalizer;
 mod settings;
-
-use anyhow::{Context, Result};
-use audio::resample::LinearResampler;
+mod speech;
+#[cfg(test)]
+mod testing;
+mod wake;
+
+use anyhow::{bail, Result};
+use assistant::{
+    cue::Cue,
+    gate::{CommandGate, Decision},
+    Clock, Effect, Event, Prompt, SampleClock, Session, State, SystemClock,
+};
+use audio::{
+    downmix::ChannelStrategy, preroll::PreRoll, recorder::DebugRecorder,
+    resample::PolyphaseResampler, supervisor::CaptureSupervisor, wav::WavSource, AudioSource,
+    Chunk,
+};
 use colored::Colorize;
-use commands::{executor, parser::parse_command};
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use normalizer::{audio::AudioNormalizer, text};
+use commands::{executor, parser::parse_command, Command};
+use normalizer::{
+    audio::AudioNormalizer,
+    calibration::{CalibrationStore, Calibrator, NoiseProfile, NoiseTracker, Thresholds},
+    vad::{Vad, VadConfig, VadEvent},
+};
 use settings::manager::SettingsManager;
-use std::sync::mpsc;
+use speech::{
+    confidence,
+    language::{Language, MultiRecognizer},
+    loader::{self, LoadedLanguage, LoaderConfig},
+    models::{self, ModelSize},
+    Transcript,
+};
 use std::{
+    collections::VecDeque,
     io,
-    time::{Duration, Instant},
+    sync::mpsc::{Receiver, TryRecvError},
+    time::Duration,
 };
-use vosk::{set_log_level, DecodingState, LogLevel, Model, Recognizer};
+use vosk::{set_log_level, LogLevel};
 
 const TARGET_SR: u32 = 16_000;
 const SETTINGS_FILE_PATH: &str = "settings.json";
-const COMMAND_WINDOW: Duration = Duration::from_secs(6);
+const COMMAND_WINDOW_SECS: f32 = 6.0;
+const CALIBRATION_SECS: f32 = 3.0;
+const PRE_ROLL_MS: u32 = 500;
+const ARM_REPLAY_MS: u32 = 300;
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
 
-enum Languages {
-    English,
-    Ukrainian,
-}
+fn main() -> Result<()> {
+    let mut settings_manager = SettingsManager::new(String::from(SETTINGS_FILE_PATH));
 
-enum Models {
-    Nano,
-    Small,
-    Normal,
-}
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-fn main() -> Result<()> {
-    let settings_manager = SettingsManager::new(String::from(SETTINGS_FILE_PATH));
+    if args.iter().any(|arg| arg == "--list-devices") {
+        return audio::device::print_input_devices(&cpal::default_host());
+    }
 
-    let text_mode = matches!(settings_manager.get_setting("text_mode").as_str(), "true");
+    if args.iter().any(|arg| arg == "--list-models") {
+        models::print_models(&models::search_dirs(
+            &settings_manager.get_setting("models_dir"),
+        ));
+        return Ok(());
+    }
+
+    let input_file = match args.iter().position(|arg| arg == "--input") {
+        Some(i) => match args.get(i + 1).filter(|path| !path.starts_with("--")) {
+            Some(path) => Some(path.clone()),
+            None => bail!("Usage: aurora --input <file.wav>"),
+        },
+        None => None,
+    };
+
+    let text_mode = input_file.is_none()
+        && matches!(settings_manager.get_setting("text_mode").as_str(), "true");
 
     settings_manager.print_settings();
     println!();
 
     if text_mode {
-        loop {
-            let mut cmd = String::new();
-            println!("{}", "[*] Waiting for command...".cyan().italic());
-            io::stdin().read_line(&mut cmd)?;
-            let cmd = parse_command(cmd.trim());
-            println!(
-                "{}",
-                format!("[+] Recognized command {:?}", cmd).green().bold()
-            );
-
-            match executor::execute(cmd) {
-                commands::CommandResult::Running | commands::CommandResult::EndConversation => {}
-                commands::CommandResult::Quit => return Ok(()),
-            }
-        }
+        run_text_mode(&mut settings_manager)
     } else {
-        let model = match settings_manager.get_setting("model").as_str() {
-            "nano" => Models::Nano,
-            "small" => Models::Small,
-            "normal" => Models::Normal,
-            model => {
+        let model_size = match ModelSize::parse(&settings_manager.get_setting("model")) {
+            Some(size) => size,
+            None => {
                 println!(
                     "{}\n{}\n{}",
-                    format!("[!] Unknown model: {}\n", model).red(),
+                    format!(
+                        "[!] Unknown model: {}\n",
+                        settings_manager.get_setting("model")
+                    )
+                    .red(),
                     "[*] Available models: nano, small, normal".magenta(),
                     "[*] Using default model (Normal)".cyan()
                 );
-                Models::Normal
+                ModelSize::Normal
             }
         };
 
-        let language = match settings_manager.get_setting("language").as_str() {
-            "uk" => Languages::Ukrainian,
-            "en" => Languages::English,
+        let languages = match settings_manager.get_setting("language").as_str() {
+            "uk" => vec![Language::Ukrainian],
+            "en" => vec![Language::English],
+            "bilingual" => vec![Language::Ukrainian, Language::English],
             lang => {
                 println!(
                     "{}\n{}\n{}",
                     format!("[!] Unknown language: {}\n", lang).red(),
-                    "[*] Available languages: English, Ukrainian".magenta(),
+                    "[*] Available languages: English, Ukrainian, bilingual".magenta(),
                     "[*] Using default language (English)".cyan()
                 );
-                Languages::English
+                vec![Language::English]
             }
         };
 
-        let mut model_path = String::from("models/stt/");
-
-        model_path.push_str(match language {
-            Languages::English => "en",
-            Languages::Ukrainian => "uk",
-        });
-
-        model_path.push('-');
-
-        model_path.push_str(match model {
-            Models::Nano => "nano",
-            Models::Small => "small",
-            Models::Normal => "normal",
-        });
+        let model_dirs = models::search_dirs(&settings_manager.get_setting("models_dir"));
+        let (found_models, skipped_models) = models::scan(&model_dirs);
+        for note in &skipped_models {
+            println!("{}", format!("[!] Skipping model {note}").yellow());
+        }
+        let mut selected_models = Vec::new();
+        for &lang in &languages {
+            match models::select(&found_models, lang, model_size) {
+                Some(info) => {
+                    if info.size != model_size {
+                        println!(
+                            "{}",
+                            format!(
+                                "[!] No {} model for {}, using {}",
+                                model_size.name(),
+                                lang.code(),
+                                info.size.name()
+                            )
+                            .yellow()
+                        );
+                    }
+                    selected_models.push((lang, info.clone()));
+                }
+                None => println!(
+                    "{}",
+                    format!("[!] No model for {} found", lang.code()).yellow()
+                ),
+            }
+        }
+        if selected_models.is_empty() {
+            let searched: Vec<String> = model_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect();
+            bail!(
+                "No Vosk model found in {} (see --list-models)",
+                searched.join(", ")
+            );
+        }
+        let mut language = selected_models[0].0;
 
         let continuous_mode = matches!(
             settings_manager.get_setting("continuous_mode").as_str(),
             "true"
         );
 
+        let command_window = Duration::from_secs_f32(
+            settings_manager
+                .get_setting("command_window")
+                .parse::<f32>()
+                .ok()
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .unwrap_or(COMMAND_WINDOW_SECS),
+        );
+        let timeout_cue = match Cue::parse(&settings_manager.get_setting("timeout_cue")) {
+            Some(cue) => cue,
+            None => {
+                println!(
+                    "{}\n{}\n{}",
+                    format!(
+                        "[!] Unknown timeout cue: {}\n",
+                        settings_manager.get_setting("timeout_cue")
+                    )
+                    .red(),
+                    "[*] Available cues: notification, bell, none".magenta(),
+                    "[*] Using no cue".cyan()
+                );
+                Cue::Silent
+            }
+        };
+
         set_log_level(LogLevel::Error);
 
         println!();
         println!("{}", "[*] Initializing...".magenta().italic());
 
-        let mut norm = AudioNormalizer::new();
-        let model = Model::new(model_path).context("Vosk model not found")?;
-
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device found")?;
-
-        let supported = device.default_input_config()?;
-        let config = supported.config();
-
-        println!(
-            "{}{}",
-            "[*] Input device: ".magenta().bold(),
-            format!("{}", device.description()?).magenta()
-        );
-        println!();
-        println!(
-            "{}\n{}\n{}\n{}",
-            "[*] Format:".bold().magenta(),
-            format!("    - channels={}", config.channels).magenta(),
-            format!("    - sample_rate={}", config.sample_rate).magenta(),
-            format!("    - sample_format={:?}", supported.sample_format()).magenta(),
+        let noise_suppression = matches!(
+            settings_manager.get_setting("noise_suppression").as_str(),
+            "true"
         );
 
-        let (tx, rx) = mpsc::channel::<Vec<i16>>();
-        let channels = config.channels as usize;
+        let mut norm = AudioNormalizer::new(noise_suppression);
 
-        let stream = match supported.sample_format() {
-            cpal::SampleFormat::F32 => build_stream_f32(&device, &config, channels, tx)?,
-            _ => anyhow::bail!(
-                "Now supporting only f32. Your format: {:?}.",
-                supported.sample_format()
-            ),
-        };
+        let fuzzy_threshold: f64 = settings_manager
+            .get_setting("fuzzy_matcher_threshold")
+            .parse()
+            .unwrap_or(0.85);
 
-        stream.play()?;
+        let channel_strategy = match ChannelStrategy::parse(
+            &settings_manager.get_setting("channel_strategy"),
+        ) {
+            Some(strategy) => strategy,
+            None => {
+                println!(
+                    "{}\n{}\n{}",
+                    format!(
+                        "[!] Unknown channel strategy: {}\n",
+                        settings_manager.get_setting("channel_strategy")
+                    )
+                    .red(),
+                    "[*] Available strategies: average, channel:<n>, loudest, beamform:<spacing_mm>:<angle_deg>"
+                        .magenta(),
+                    "[*] Using default strategy (average)".cyan()
+                );
+                ChannelStrategy::Average
+            }
+        };
 
-        let mut rec =
-            Recognizer::new(&model, TARGET_SR as f32).context("Recognizer::new failed")?;
+        let mut source: Box<dyn AudioSource> = match &input_file {
+            Some(path) => {
+                println!("{}{}", "[*] Input file: ".magenta().bold(), path.magenta());
+                Box::new(WavSource::open(path, channel_strategy)?)
+            }
+            None => Box::new(CaptureSupervisor::start(
+                cpal::default_host(),
+                settings_manager.get_setting("input_device"),
+                fuzzy_threshold,
+                channel_strategy,
+            )?),
+        };
 
-        let input_sr = config.sample_rate;
-        let mut rs = LinearResampler::new(input_sr, TARGET_SR);
+        let min_confidence: f32 = settings_manager
+            .get_setting("min_confidence")
+            .parse()
+            .unwrap_or(0.6);
+        let mut gate = CommandGate::new(TARGET_SR, min_confidence);
+
+        let mut input_sr = source.sample_rate();
+        let mut rs = PolyphaseResampler::new(input_sr, TARGET_SR);
+        let mut vad = Vad::new(TARGET_SR, VadConfig::default());
+        let mut preroll = PreRoll::new(TARGET_SR, PRE_ROLL_MS);
+
+        let mut recorder = if settings_manager.get_setting("debug_audio") == "true" {
+            let dir = settings_manager.get_setting("debug_audio_dir");
+            let dir = if dir.is_empty() {
+                String::from("debug_audio")
+            } else {
+                dir
+            };
+            println!(
+                "{}{}",
+                "[*] Recording debug audio to ".magenta().bold(),
+                dir.magenta()
+            );
+            Some(DebugRecorder::new(dir, input_sr)?)
+        } else {
+            None
+        };
 
-        let wake_word = match language {
-            Languages::English => "aurora",
-            Languages::Ukrainian => "аврора",
+        let calibration_file = settings_manager.get_setting("calibration_file");
+        let mut calibration = CalibrationStore::load(if calibration_file.is_empty() {
+            String::from("calibration.json")
+        } else {
+            calibration_file
+        });
+        let mut calibrate_now = args.iter().any(|arg| arg == "--calibrate");
+        let mut calibrator: Option<Calibrator> = None;
+        let mut tracker: Option<NoiseTracker> = None;
+        let mut device_name = String::new();
+
+        let max_alternatives: u16 = settings_manager
+            .get_setting("max_alternatives")
+            .parse()
+            .unwrap_or(1);
+        let command_grammar = settings_manager.get_setting("command_grammar") != "false";
+        let wake_threshold: f64 = settings_manager
+            .get_setting("wake_threshold")
+            .parse()
+            .unwrap_or(0.8);
+
+        let loader_config = LoaderConfig {
+            sample_rate: TARGET_SR as f32,
+            max_alternatives,
+            command_grammar,
+            wake_words: settings_manager.get_value("wake_words"),
+            wake_threshold,
         };
 
-        let mut armed = false;
-        let mut armed_until = Instant::now();
-        let mut in_conversation = false;
+        let mut recs = Vec::new();
+        let mut listeners = Vec::new();
+        for (lang, info) in selected_models {
+            let (command_rec, listener) = loader::load(lang, &info, &loader_config)?.into_parts();
+            recs.push((lang, command_rec));
+            listeners.push((lang, listener));
+        }
+        let mut rec = MultiRecognizer::new(recs);
+        let persist_language = settings_manager.get_setting("persist_language") == "true";
+        let mut switching: Option<Receiver<Result<LoadedLanguage>>> = None;
+
+        let replay_clock = input_file.as_ref().map(|_| SampleClock::new(TARGET_SR));
+        let clock: Box<dyn Clock> = match &replay_clock {
+            Some(clock) => Box::new(clock.clone()),
+            None => Box::new(SystemClock),
+        };
+        let mut session = Session::new(clock, Some(command_window), continuous_mode);
 
         println!("{}", "[+] Initialization complete!".green().bold());
         println!();
@@ -172,123 +319,452 @@ fn main() -> Result<()> {
 
         println!("{}", "[*] Waiting for wake word...".cyan().italic());
 
+        let mut effects: VecDeque<Effect> = VecDeque::new();
         loop {
-            let mono_in = rx.recv().context("Audio channel closed")?;
+            // Tick even without audio so the window closes while capture stalls.
+            effects.extend(session.handle(Event::Tick));
+            while let Some(effect) = effects.pop_front() {
+                match effect {
+                    Effect::Execute(cmd) => {
+                        let result = if gate.already_ran(&cmd) {
+                            println!("{}", "[*] Already executed".cyan());
+                            commands::CommandResult::Running
+                        } else {
+                            executor::execute(cmd, Some(language))
+                        };
+                        effects.extend(session.handle(Event::Finished(result)));
+                    }
+                    Effect::Calibrate => calibrate_now = true,
+                    Effect::SwitchLanguage(lang) => {
+                        if switching.is_some() {
+                            println!("{}", "[!] Already switching language".yellow());
+                        } else if !rec.is_multilingual() && rec.language() == lang {
+                            println!("{}", format!("[*] Already using {}", lang.code()).cyan());
+                        } else if let Some(info) = models::select(&found_models, lang, model_size) {
+                            println!(
+                                "{}",
+                                format!("[*] Loading {} model in the background...", lang.code())
+                                    .cyan()
+                            );
+                            switching = Some(loader::load_in_background(
+                                lang,
+                                info.clone(),
+                                loader_config.clone(),
+                            ));
+                        } else {
+                            println!(
+                                "{}",
+                                format!("[!] No model for {} found", lang.code()).yellow()
+                            );
+                        }
+                    }
+                    Effect::EndConversation => {
+                        println!("{}", "[+] Ending conversation".green().bold());
+                    }
+                    Effect::Timeout => {
+                        on_timeout(timeout_cue);
+                        gate.reset();
+                        rec.reset();
+                        if let Some(recorder) = recorder.as_mut() {
+                            recorder.finish_clip("");
+                        }
+                    }
+                    Effect::Prompt(Prompt::ReadyForNext) => {
+                        if let Some(recorder) = recorder.as_mut() {
+                            recorder.start_clip();
+                        }
+                        println!("{}", "[*] Ready for next command...".cyan());
+                    }
+                    Effect::Prompt(Prompt::WaitingForWakeWord) => {
+                        println!();
+                        println!("{}", "[*] Waiting for wake word...".cyan().italic());
+                    }
+                    Effect::Quit => return Ok(()),
+                }
+            }
+
+            let mono_in = match source.next_chunk(TICK_INTERVAL) {
+                Chunk::Audio(samples) => samples,
+                Chunk::Pending => continue,
+                Chunk::End => {
+                    println!("{}", "[*] End of input".cyan().italic());
+                    return Ok(());
+                }
+            };
+
+            // Swap in the other language once its model finished loading, but
+            // never in the middle of an utterance or an open command window.
+            let between_utterances = !session.is_armed() && !vad.is_speech();
+            if let Some(rx) = switching.as_ref().filter(|_| between_utterances) {
+                match rx.try_recv() {
+                    Ok(Ok(loaded)) => {
+                        let lang = loaded.language;
+                        let (command_rec, listener) = loaded.into_parts();
+                        rec = MultiRecognizer::new(vec![(lang, command_rec)]);
+                        listeners = vec![(lang, listener)];
+                        language = lang;
+                        gate.reset_early();
+                        switching = None;
+                        println!(
+                            "{}",
+                            format!("[+] Switched to {}", lang.code()).green().bold()
+                        );
+                        if persist_language {
+                            save_language(&mut settings_manager, lang);
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        switching = None;
+                        println!(
+                            "{}",
+                            format!("[!] Unable to switch language: {err:#}").yellow()
+                        );
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        switching = None;
+                        println!("{}", "[!] Language loader stopped".yellow());
+                    }
+                    Err(TryRecvError::Empty) => {}
+                }
+            }
+
+            if source.sample_rate() != input_sr {
+                input_sr = source.sample_rate();
+                rs = PolyphaseResampler::new(input_sr, TARGET_SR);
+            }
 
             let mut chunk_16k = rs.process(&mono_in);
+            if let Some(clock) = &replay_clock {
+                clock.advance(chunk_16k.len());
+            }
+
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.raw(&mono_in, input_sr);
+                recorder.resampled(&chunk_16k);
+            }
 
-            if !norm.process(&mut chunk_16k) {
+            if source.device_name() != device_name {
+                device_name = source.device_name();
+                match calibration.get(&device_name) {
+                    Some(profile) if !calibrate_now => {
+                        println!(
+                            "{}",
+                            format!("[*] Loaded noise profile for {device_name}").magenta()
+                        );
+                        let noise_rms = profile.noise_rms;
+                        apply_thresholds(Thresholds::from_noise(noise_rms), &mut vad, &mut norm);
+                        tracker = Some(NoiseTracker::new(noise_rms));
+                    }
+                    // Replayed files keep the defaults unless asked explicitly.
+                    _ if input_file.is_none() => calibrate_now = true,
+                    _ => {}
+                }
+            }
+
+            if calibrate_now {
+                calibrate_now = false;
+                println!(
+                    "{}",
+                    format!("[*] Calibrating noise floor, stay quiet for {CALIBRATION_SECS}s...")
+                        .cyan()
+                        .italic()
+                );
+                calibrator = Some(Calibrator::new(TARGET_SR, CALIBRATION_SECS));
+            }
+
+            if let Some(noise_rms) = calibrator.as_mut().map(|c| c.feed(&chunk_16k)) {
+                let Some(noise_rms) = noise_rms else {
+                    continue;
+                };
+                calibrator = None;
+                println!("{}", "[+] Calibration complete".green().bold());
+                apply_thresholds(Thresholds::from_noise(noise_rms), &mut vad, &mut norm);
+                tracker = Some(NoiseTracker::new(noise_rms));
+                save_noise_profile(&mut calibration, &device_name, noise_rms);
                 continue;
             }
 
-            let state = rec.accept_waveform(&chunk_16k)?;
+            let events = vad.process(&chunk_16k);
 
-            if matches!(state, DecodingState::Finalized) {
-                let res = rec.result();
-                let text: &str = match res {
-                    vosk::CompleteResult::Single(single) => single.text,
-                    vosk::CompleteResult::Multiple(multiple) => {
-                        if let Some(first) = multiple.alternatives.first() {
-                            first.text
-                        } else {
-                            ""
-                        }
+            // The window only runs out while the room is quiet.
+            if session.is_armed() && vad.is_speech() {
+                session.handle(Event::Extend);
+            }
+
+            if !vad.is_speech() && events.is_empty() {
+                if let Some(tracker) = tracker.as_mut() {
+                    if let Some(thresholds) = tracker.update(&chunk_16k, TARGET_SR) {
+                        println!("{}", "[*] Background noise changed".magenta());
+                        apply_thresholds(thresholds, &mut vad, &mut norm);
+                        save_noise_profile(&mut calibration, &device_name, tracker.noise_rms());
+                    }
+                }
+            }
+            norm.process(&mut chunk_16k);
+
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.normalized(&chunk_16k);
+            }
+
+            preroll.push(&chunk_16k);
+
+            let speech_ended = events.contains(&VadEvent::SpeechEnd);
+            if !vad.is_speech() && !speech_ended {
+                continue;
+            }
+
+            // The VAD needs a few frames to confirm onset, so replay what came before it.
+            let audio = if events.contains(&VadEvent::SpeechStart) {
+                preroll.take()
+            } else {
+                chunk_16k
+            };
+
+            let transcript = if !session.is_armed() {
+                let mut heard = None;
+                for (lang, listener) in listeners.iter_mut() {
+                    if let Some(hit) = listener.accept(&audio, speech_ended)? {
+                        heard.get_or_insert((*lang, hit));
                     }
+                }
+                let Some((wake_language, hit)) = heard else {
+                    continue;
                 };
+                for (_, listener) in listeners.iter_mut() {
+                    listener.reset();
+                }
+                language = wake_language;
+                println!("{}", format!("[*] You said: {}", hit.heard).cyan());
+                if let Some(profile) = &hit.wake.profile {
+                    println!("{}", format!("[*] Profile: {profile}").magenta());
+                }
+
+                session.handle(Event::WakeWord);
+                rec.reset();
 
-                if text.is_empty() {
+                // Hand the rest of the utterance over to the full recognizer.
+                let rest = if hit.rest.is_empty() && vad.is_speech() {
+                    preroll.take_last(ARM_REPLAY_MS)
+                } else {
+                    hit.rest
+                };
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.start_clip();
+                    recorder.extend_clip(&rest);
+                }
+                let command = rec.accept(&rest, speech_ended)?.unwrap_or_default();
+
+                if command.text.is_empty() {
+                    println!("{}", "[+] Wake word heard, say command...".green().bold());
+                    if session.state() == State::InConversation {
+                        println!("{}", "[*] Continuous mode".cyan().bold());
+                    }
                     continue;
                 }
 
-                if !armed {
-                    println!("{}", format!("[*] You said: {}", text).cyan());
-                    if contains_wake(text, wake_word) {
-                        armed = true;
-                        armed_until = Instant::now() + COMMAND_WINDOW;
-                        in_conversation = continuous_mode;
-                        println!("{}", "[+] Wake word heard, say command...".green().bold());
-                        if continuous_mode {
-                            println!("{}", "[*] Continuous mode".cyan().bold());
+                println!("{}", "[+] Wake word heard".green().bold());
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.finish_clip(&command.text);
+                }
+                command
+            } else {
+                let Some(transcript) = rec.accept(&audio, speech_ended)? else {
+                    if session.is_open() {
+                        if let Some(cmd) = gate.partial(&rec.partial(), audio.len()) {
+                            println!("{}", format!("[+] Early command: {:?}", cmd).green().bold());
+                            executor::execute(cmd, Some(language));
                         }
-                        rec.reset();
                     }
-                } else {
-                    if Instant::now() <= armed_until {
-                        println!("{}", format!("[*] Your command: {text}").cyan());
-                        let cmd = parse_command(text);
-                        println!(
-                            "{}",
-                            format!("[+] Recognized command: {:?}", cmd).green().bold()
-                        );
+                    continue;
+                };
 
-                        match executor::execute(cmd) {
-                            commands::CommandResult::Running => {}
-                            commands::CommandResult::EndConversation => {
-                                println!("{}", "[+] Ending conversation".green().bold());
-                                in_conversation = false;
-                            }
-                            commands::CommandResult::Quit => return Ok(()),
-                        }
+                if transcript.text.is_empty() {
+                    gate.reset_early();
+                    continue;
+                }
 
-                        if in_conversation {
-                            armed_until = Instant::now() + COMMAND_WINDOW;
-                            println!("{}", "[*] Ready for next command...".cyan());
-                        } else {
-                            armed = false;
-                            println!();
-                            println!("{}", "[*] Waiting for wake word...".cyan().italic());
-                        }
-                    } else {
-                        println!("{}", "[!] Timeout".yellow());
-                        in_conversation = false;
-                        armed = false;
-                        println!();
-                        println!("{}", "[*] Waiting for wake word...".cyan().italic());
+                if let Some(recorder) = recorder.as_mut() {
+                    recorder.finish_clip(&transcript.text);
+                }
+                transcript
+            };
+
+            if session.is_open() {
+                let text = transcript.text.as_str();
+                println!("{}", format!("[*] Your command: {text}").cyan());
+                if rec.is_multilingual() {
+                    language = rec.language();
+                    println!(
+                        "{}",
+                        format!("[*] Language: {} ({})", language.code(), rec.reason()).magenta()
+                    );
+                }
+
+                if !gate.is_confirming() {
+                    if let Some(pick) = rec
+                        .last_pick()
+                        .filter(|p| p.total > 1 && p.transcript.text == text)
+                    {
+                        println!("{}", format!("[*] Picked {}", pick.reason()).cyan());
                     }
+                }
+
+                let decision = gate.decide(&transcript, rec.last_pick());
+                print_decision(&decision, &transcript, gate.min_confidence(), language);
 
+                let Some(cmd) = decision.command(text) else {
+                    session.handle(Event::Extend);
+                    if let Some(recorder) = recorder.as_mut() {
+                        recorder.start_clip();
+                    }
                     rec.reset();
-                }
+                    continue;
+                };
+                effects.extend(session.handle(Event::Command(cmd)));
             }
+
+            rec.reset();
         }
     }
 }
 
-fn build_stream_f32(
-    device: &cpal::Device,
-    config: &cpal::StreamConfig,
-    channels: usize,
-    tx: mpsc::Sender<Vec<i16>>,
-) -> Result<cpal::Stream> {
-    let err_fn = |err| eprintln!("Stream error: {err}");
-
-    let stream = device.build_input_stream(
-        config,
-        move |data: &[f32], _info| {
-            let mut mono = Vec::with_capacity(data.len() / channels);
-
-            for frame in data.chunks(channels) {
-                let sample = if channels >= 2 {
-                    (frame[0] + frame[1]) * 0.5
-                } else {
-                    frame[0]
-                };
+// Typed commands, for trying Aurora without a microphone.
+fn run_text_mode(settings_manager: &mut SettingsManager) -> Result<()> {
+    let mut language = Language::from_code(&settings_manager.get_setting("language"));
+    let persist_language = settings_manager.get_setting("persist_language") == "true";
+    // Typed commands need no wake word and never time out.
+    let mut session = Session::new(SystemClock, None, true);
+    loop {
+        if !session.is_armed() {
+            session.handle(Event::WakeWord);
+        }
+
+        let mut cmd = String::new();
+        println!("{}", "[*] Waiting for command...".cyan().italic());
+        io::stdin().read_line(&mut cmd)?;
+        let cmd = parse_command(cmd.trim());
+        println!(
+            "{}",
+            format!("[+] Recognized command {:?}", cmd).green().bold()
+        );
 
-                let s = sample.clamp(-1.0, 1.0);
-                let i = (s * i16::MAX as f32) as i16;
-                mono.push(i);
+        let mut effects: VecDeque<Effect> = session.handle(Event::Command(cmd)).into();
+        while let Some(effect) = effects.pop_front() {
+            match effect {
+                Effect::Execute(cmd) => {
+                    let result = executor::execute(cmd, language);
+                    effects.extend(session.handle(Event::Finished(result)));
+                }
+                Effect::Calibrate => println!(
+                    "{}",
+                    "[!] Calibration is only available in voice mode".yellow()
+                ),
+                Effect::SwitchLanguage(lang) => {
+                    language = Some(lang);
+                    println!(
+                        "{}",
+                        format!("[+] Switched to {}", lang.code()).green().bold()
+                    );
+                    if persist_language {
+                        save_language(settings_manager, lang);
+                    }
+                }
+                Effect::Quit => return Ok(()),
+                Effect::EndConversation | Effect::Timeout | Effect::Prompt(_) => {}
             }
+        }
+    }
+}
 
-            let _ = tx.send(mono);
-        },
-        err_fn,
-        None,
-    )?;
+fn save_language(settings_manager: &mut SettingsManager, language: Language) {
+    // Saving a single language would turn bilingual mode off for good.
+    if settings_manager.get_setting("language") == "bilingual" {
+        println!(
+            "{}",
+            "[*] Keeping \"bilingual\" in settings, the switch lasts until restart".cyan()
+        );
+        return;
+    }
+    if let Err(err) = settings_manager.set_setting("language", language.code()) {
+        println!("{}", format!("[!] Unable to save language: {err}").yellow());
+    }
+}
 
-    Ok(stream)
+fn on_timeout(cue: Cue) {
+    println!("{}", "[!] Timeout".yellow());
+    cue.play("Command window closed");
 }
 
-fn contains_wake(text: &str, word: &str) -> bool {
-    let t = text::normalize(text);
-    let w = text::normalize(word);
-    t.contains(&w)
+fn print_decision(
+    decision: &Decision,
+    transcript: &Transcript,
+    min_confidence: f32,
+    language: Language,
+) {
+    match decision {
+        Decision::Confirmed(_) => println!("{}", "[+] Confirmed".green().bold()),
+        Decision::Cancelled => println!("{}", "[*] Cancelled".cyan()),
+        Decision::Accepted(cmd) | Decision::Rejected(cmd, _) | Decision::Confirm(cmd, _) => {
+            println!(
+                "{}",
+                format!("[+] Recognized command: {:?}", cmd).green().bold()
+            );
+        }
+    }
+    match decision {
+        Decision::Rejected(cmd, conf) => println!(
+            "{}",
+            format!(
+                "[!] Rejected {:?}: confidence {:.2} < {:.2} ({})",
+                cmd,
+                conf,
+                min_confidence,
+                confidence::word_scores(transcript)
+            )
+            .yellow()
+        ),
+        Decision::Confirm(cmd, conf) => {
+            println!(
+                "{}",
+                format!(
+                    "[!] Unsure about {:?}: confidence {:.2} ({})",
+                    cmd,
+                    conf,
+                    confidence::word_scores(transcript)
+                )
+                .yellow()
+            );
+            println!("{}", confirm_prompt(cmd, language).cyan().bold());
+        }
+        _ => {}
+    }
+}
+
+fn confirm_prompt(cmd: &Command, language: Language) -> String {
+    match language {
+        Language::English => format!("[?] Run {:?}? Say yes or no", cmd),
+        Language::Ukrainian => format!("[?] Виконати {:?}? Скажіть так або ні", cmd),
+    }
+}
+
+fn apply_thresholds(thresholds: Thresholds, vad: &mut Vad, norm: &mut AudioNormalizer) {
+    vad.set_min_rms(thresholds.min_rms);
+    norm.set_max_gain(thresholds.agc_max_gain);
+    println!(
+        "{}",
+        format!(
+            "[*] Silence threshold: min_rms={:.4}, max_gain={:.1}",
+            thresholds.min_rms, thresholds.agc_max_gain
+        )
+        .magenta()
+    );
+}
+
+fn save_noise_profile(store: &mut CalibrationStore, device: &str, noise_rms: f32) {
+    if let Err(err) = store.set(device, NoiseProfile::new(noise_rms)) {
+        println!(
+            "{}",
+            format!("[!] Unable to save noise profile: {err}").yellow()
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use super::denoise::NoiseSuppressor;
+
 #[derive(Debug, Clone)]
 pub struct DcBlocker {
     mean: f32,
@@ -42,6 +44,10 @@ impl Agc {
         }
     }
 
+    pub fn set_max_gain(&mut self, max_gain: f32) {
+        self.max_gain = max_gain;
+    }
+
     pub fn process(&mut self, buf: &mut [i16]) {
         if buf.is_empty() {
             return;
@@ -92,32 +98,36 @@ pub fn rms_i16(buf: &[i16]) -> f32 {
     (sum / buf.len() as f32).sqrt()
 }
 
-#[derive(Debug, Clone)]
 pub struct AudioNormalizer {
     dc: DcBlocker,
+    denoise: Option<NoiseSuppressor>,
     agc: Agc,
     clip_threshold: i16,
-    min_rms: f32,
 }
 
 impl AudioNormalizer {
-    pub fn new() -> Self {
+    pub fn new(noise_suppression: bool) -> Self {
         Self {
             dc: DcBlocker::new(0.999),
+            denoise: noise_suppression.then(NoiseSuppressor::new),
             agc: Agc::new(0.12, 8.0, 0.95),
             clip_threshold: (i16::MAX as f32 * 0.95) as i16,
-            min_rms: 0.008,
         }
     }
 
-    pub fn process(&mut self, buf: &mut [i16]) -> bool {
+    pub fn set_max_gain(&mut self, max_gain: f32) {
+        self.agc.set_max_gain(max_gain);
+    }
+
+    pub fn process(&mut self, buf: &mut [i16]) {
         self.dc.process_buf(buf);
+        if let Some(denoise) = self.denoise.as_mut() {
+            denoise.process(buf);
+        }
         self.agc.process(buf);
 
         for s in buf.iter_mut() {
             *s = soft_clip_i16(*s, self.clip_threshold);
         }
-
-        rms_i16(buf) >= self.min_rms
     }
 }
@@ -0,0 +1,262 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::audio::rms_i16;
+
+const FRAME_MS: u32 = 20;
+const MIN_RMS_FACTOR: f32 = 2.0;
+const MIN_RMS_RANGE: (f32, f32) = (0.001, 0.05);
+// Keep amplified background noise below this level.
+const NOISE_CEILING: f32 = 0.02;
+const MAX_GAIN_RANGE: (f32, f32) = (1.0, 8.0);
+const TRACK_SECS: f32 = 60.0;
+const RETUNE_DB: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub min_rms: f32,
+    pub agc_max_gain: f32,
+}
+
+impl Thresholds {
+    pub fn from_noise(noise_rms: f32) -> Self {
+        let noise_rms = noise_rms.max(1e-6);
+        Self {
+            min_rms: (noise_rms * MIN_RMS_FACTOR).clamp(MIN_RMS_RANGE.0, MIN_RMS_RANGE.1),
+            agc_max_gain: (NOISE_CEILING / noise_rms).clamp(MAX_GAIN_RANGE.0, MAX_GAIN_RANGE.1),
+        }
+    }
+}
+
+pub struct Calibrator {
+    frame_len: usize,
+    frames_needed: usize,
+    pending: Vec<i16>,
+    levels: Vec<f32>,
+}
+
+impl Calibrator {
+    pub fn new(sample_rate: u32, secs: f32) -> Self {
+        let frame_len = (sample_rate * FRAME_MS / 1000).max(1) as usize;
+        let frames_needed = ((secs * 1000.0) as u32 / FRAME_MS).max(1) as usize;
+        Self {
+            frame_len,
+            frames_needed,
+            pending: Vec::with_capacity(frame_len),
+            levels: Vec::with_capacity(frames_needed),
+        }
+    }
+
+    // Returns the measured noise RMS once enough audio has been seen.
+    pub fn feed(&mut self, buf: &[i16]) -> Option<f32> {
+        for &s in buf {
+            self.pending.push(s);
+            if self.pending.len() == self.frame_len {
+                self.levels.push(rms_i16(&self.pending));
+                self.pending.clear();
+            }
+            if self.levels.len() >= self.frames_needed {
+                break;
+            }
+        }
+
+        if self.levels.len() < self.frames_needed {
+            return None;
+        }
+
+        // The median ignores a cough or a door slam during calibration.
+        let mut levels = std::mem::take(&mut self.levels);
+        levels.sort_by(f32::total_cmp);
+        Some(levels[levels.len() / 2])
+    }
+}
+
+pub struct NoiseTracker {
+    floor_db: f32,
+    applied_db: f32,
+}
+
+impl NoiseTracker {
+    pub fn new(noise_rms: f32) -> Self {
+        let db = to_db(noise_rms);
+        Self {
+            floor_db: db,
+            applied_db: db,
+        }
+    }
+
+    pub fn noise_rms(&self) -> f32 {
+        10f32.powf(self.floor_db / 20.0)
+    }
+
+    // Feed non-speech audio; returns new thresholds when the floor drifted enough.
+    pub fn update(&mut self, buf: &[i16], sample_rate: u32) -> Option<Thresholds> {
+        if buf.is_empty() {
+            return None;
+        }
+
+        let secs = buf.len() as f32 / sample_rate as f32;
+        let alpha = (secs / TRACK_SECS).min(1.0);
+        self.floor_db += (to_db(rms_i16(buf)) - self.floor_db) * alpha;
+
+        if (self.floor_db - self.applied_db).abs() < RETUNE_DB {
+            return None;
+        }
+        self.applied_db = self.floor_db;
+        Some(Thresholds::from_noise(self.noise_rms()))
+    }
+}
+
+fn to_db(rms: f32) -> f32 {
+    20.0 * rms.max(1e-6).log10()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseProfile {
+    pub noise_rms: f32,
+    pub updated: u64,
+}
+
+impl NoiseProfile {
+    pub fn new(noise_rms: f32) -> Self {
+        Self {
+            noise_rms,
+            updated: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        }
+    }
+}
+
+pub struct CalibrationStore {
+    path: PathBuf,
+    profiles: BTreeMap<String, NoiseProfile>,
+}
+
+impl CalibrationStore {
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let profiles = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { path, profiles }
+    }
+
+    pub fn get(&self, device: &str) -> Option<&NoiseProfile> {
+        self.profiles.get(device)
+    }
+
+    pub fn set(&mut self, device: &str, profile: NoiseProfile) -> Result<()> {
+        self.profiles.insert(device.to_string(), profile);
+        fs::write(&self.path, serde_json::to_string_pretty(&self.profiles)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32, len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| {
+                let phase = i as f32 * 2.0 * std::f32::consts::PI * 440.0 / 16_000.0;
+                (phase.sin() * amplitude * i16::MAX as f32) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn thresholds_follow_noise() {
+        let quiet = Thresholds::from_noise(0.0025);
+        assert_eq!(quiet.min_rms, 0.005);
+        assert_eq!(quiet.agc_max_gain, 8.0);
+
+        let loud = Thresholds::from_noise(0.01);
+        assert_eq!(loud.min_rms, 0.02);
+        assert_eq!(loud.agc_max_gain, 2.0);
+    }
+
+    #[test]
+    fn thresholds_are_clamped() {
+        assert_eq!(
+            Thresholds::from_noise(0.0),
+            Thresholds {
+                min_rms: 0.001,
+                agc_max_gain: 8.0
+            }
+        );
+        assert_eq!(
+            Thresholds::from_noise(0.5),
+            Thresholds {
+                min_rms: 0.05,
+                agc_max_gain: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn calibrator_waits_for_enough_audio() {
+        let mut calibrator = Calibrator::new(16_000, 1.0);
+        assert_eq!(calibrator.feed(&tone(0.01, 8_000)), None);
+        let noise = calibrator.feed(&tone(0.01, 8_000)).unwrap();
+        assert!((noise - 0.01 / 2f32.sqrt()).abs() < 5e-4, "{noise}");
+    }
+
+    #[test]
+    fn calibrator_ignores_short_bursts() {
+        let mut calibrator = Calibrator::new(16_000, 1.0);
+        let mut audio = tone(0.01, 16_000);
+        audio[4_000..6_000].copy_from_slice(&tone(0.8, 2_000));
+        let noise = calibrator.feed(&audio).unwrap();
+        assert!(noise < 0.01, "{noise}");
+    }
+
+    #[test]
+    fn tracker_moves_slowly() {
+        let mut tracker = NoiseTracker::new(0.005);
+        let loud = tone(0.05, 1_600);
+
+        assert_eq!(tracker.update(&loud, 16_000), None);
+        assert!(tracker.noise_rms() < 0.006);
+
+        let retuned = (0..2_000).find_map(|_| tracker.update(&loud, 16_000));
+        let thresholds = retuned.expect("floor never moved");
+        assert!(thresholds.min_rms > 0.012, "{thresholds:?}");
+    }
+
+    #[test]
+    fn store_round_trips_profiles() {
+        let path = crate::testing::temp_path("calib.json");
+
+        let mut store = CalibrationStore::load(&path);
+        assert!(store.get("USB Mic").is_none());
+        store
+            .set(
+                "USB Mic",
+                NoiseProfile {
+                    noise_rms: 0.004,
+                    updated: 42,
+                },
+            )
+            .unwrap();
+
+        let store = CalibrationStore::load(&path);
+        assert_eq!(
+            store.get("USB Mic"),
+            Some(&NoiseProfile {
+                noise_rms: 0.004,
+                updated: 42
+            })
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
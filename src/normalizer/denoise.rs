@@ -0,0 +1,251 @@
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::{collections::VecDeque, sync::Arc};
+
+const FRAME_LEN: usize = 512;
+const HOP: usize = FRAME_LEN / 2;
+const LEARN_FRAMES: usize = 10;
+const NOISE_SMOOTH: f32 = 0.05;
+const NOISE_UPDATE_RATIO: f32 = 3.0;
+const NOISE_RISE: f32 = 1.01;
+const NOISE_MIN: f32 = 1.0;
+const PRIORI_SMOOTH: f32 = 0.98;
+const MIN_GAIN: f32 = 0.1;
+
+pub struct NoiseSuppressor {
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    noise: Vec<f32>,
+    prev_snr: Vec<f32>,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+    frames: usize,
+}
+
+impl NoiseSuppressor {
+    pub fn new() -> Self {
+        let mut planner = FftPlanner::new();
+        let bins = FRAME_LEN / 2 + 1;
+
+        // sqrt-Hann on both analysis and synthesis sums to one at 50% overlap.
+        let window = (0..FRAME_LEN)
+            .map(|i| (std::f32::consts::PI * i as f32 / FRAME_LEN as f32).sin())
+            .collect();
+
+        Self {
+            fft: planner.plan_fft_forward(FRAME_LEN),
+            ifft: planner.plan_fft_inverse(FRAME_LEN),
+            window,
+            spectrum: vec![Complex::default(); FRAME_LEN],
+            noise: vec![0.0; bins],
+            prev_snr: vec![0.0; bins],
+            input: vec![0.0; FRAME_LEN - HOP],
+            overlap: vec![0.0; FRAME_LEN],
+            output: VecDeque::from(vec![0.0; HOP]),
+            frames: 0,
+        }
+    }
+
+    pub fn process(&mut self, buf: &mut [i16]) {
+        self.input.extend(buf.iter().map(|&s| s as f32));
+
+        while self.input.len() >= FRAME_LEN {
+            self.process_frame();
+            self.input.drain(..HOP);
+        }
+
+        for s in buf.iter_mut() {
+            let y = self.output.pop_front().unwrap_or(0.0);
+            *s = y.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        }
+    }
+
+    fn process_frame(&mut self) {
+        for ((bin, &x), &w) in self
+            .spectrum
+            .iter_mut()
+            .zip(&self.input[..FRAME_LEN])
+            .zip(&self.window)
+        {
+            *bin = Complex::new(x * w, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let bins = self.noise.len();
+        for k in 0..bins {
+            let power = self.spectrum[k].norm_sqr();
+
+            // Average the first frames, then follow the floor only with bins
+            // that look like noise and creep upwards otherwise.
+            let noise = &mut self.noise[k];
+            if self.frames < LEARN_FRAMES {
+                *noise += (power - *noise) / (self.frames + 1) as f32;
+            } else if power < NOISE_UPDATE_RATIO * *noise {
+                *noise += NOISE_SMOOTH * (power - *noise);
+            } else {
+                *noise *= NOISE_RISE;
+            }
+            *noise = noise.max(NOISE_MIN);
+
+            let post_snr = power / *noise;
+            let prio_snr = PRIORI_SMOOTH * self.prev_snr[k]
+                + (1.0 - PRIORI_SMOOTH) * (post_snr - 1.0).max(0.0);
+            let gain = (prio_snr / (1.0 + prio_snr)).max(MIN_GAIN);
+
+            self.prev_snr[k] = gain * gain * post_snr;
+
+            self.spectrum[k] *= gain;
+            if k > 0 && k < FRAME_LEN - k {
+                self.spectrum[FRAME_LEN - k] *= gain;
+            }
+        }
+        self.frames += 1;
+
+        self.ifft.process(&mut self.spectrum);
+
+        let scale = 1.0 / FRAME_LEN as f32;
+        for ((acc, bin), &w) in self
+            .overlap
+            .iter_mut()
+            .zip(&self.spectrum)
+            .zip(&self.window)
+        {
+            *acc += bin.re * scale * w;
+        }
+
+        self.output.extend(self.overlap.drain(..HOP));
+        self.overlap.resize(FRAME_LEN, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f32::consts::PI;
+
+    const SR: usize = 16_000;
+
+    fn noise(len: usize, amplitude: f32, seed: u64) -> Vec<f32> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len)
+            .map(|_| rng.gen_range(-1.0..1.0) * amplitude)
+            .collect()
+    }
+
+    fn tone(len: usize, freq: f32, amplitude: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / SR as f32).sin())
+            .collect()
+    }
+
+    fn to_i16(signal: &[f32]) -> Vec<i16> {
+        signal
+            .iter()
+            .map(|&x| (x * i16::MAX as f32) as i16)
+            .collect()
+    }
+
+    fn run(signal: &[i16], chunk: usize) -> Vec<i16> {
+        let mut ns = NoiseSuppressor::new();
+        let mut out = signal.to_vec();
+        for c in out.chunks_mut(chunk) {
+            ns.process(c);
+        }
+        out
+    }
+
+    fn tone_and_residual(signal: &[i16], freq: f32) -> (f32, f32) {
+        let (mut re, mut im, mut total) = (0.0f32, 0.0f32, 0.0f32);
+        for (i, &s) in signal.iter().enumerate() {
+            let x = s as f32 / i16::MAX as f32;
+            let w = 2.0 * PI * freq * i as f32 / SR as f32;
+            re += x * w.cos();
+            im += x * w.sin();
+            total += x * x;
+        }
+        let n = signal.len() as f32;
+        let amplitude = 2.0 * (re * re + im * im).sqrt() / n;
+        let tone_power = amplitude * amplitude / 2.0;
+        (amplitude, (total / n - tone_power).max(1e-12))
+    }
+
+    fn snr_db(signal: &[i16], freq: f32) -> f32 {
+        let (amplitude, residual) = tone_and_residual(signal, freq);
+        10.0 * (amplitude * amplitude / 2.0 / residual).log10()
+    }
+
+    fn power(signal: &[i16]) -> f32 {
+        signal
+            .iter()
+            .map(|&s| (s as f32 / i16::MAX as f32).powi(2))
+            .sum::<f32>()
+            / signal.len() as f32
+    }
+
+    #[test]
+    fn clean_signal_is_reconstructed_with_latency() {
+        let mut signal = vec![0i16; SR / 4];
+        signal.extend(to_i16(&tone(SR / 4, 440.0, 0.5)));
+
+        let out = run(&signal, 480);
+
+        for i in 3 * FRAME_LEN..signal.len() {
+            let expected = signal[i - FRAME_LEN];
+            assert!(
+                (out[i] - expected).abs() <= 2,
+                "sample {i}: {} vs {expected}",
+                out[i]
+            );
+        }
+    }
+
+    #[test]
+    fn stationary_noise_is_attenuated() {
+        let signal = to_i16(&noise(2 * SR, 0.1, 1));
+        let out = run(&signal, 480);
+
+        let before = power(&signal[SR..]);
+        let after = power(&out[SR..]);
+        let reduction = 10.0 * (before / after).log10();
+        assert!(reduction > 12.0, "reduction {reduction:.1} dB");
+    }
+
+    #[test]
+    fn tone_in_noise_snr_improves() {
+        let len = 2 * SR;
+        let mut clean = vec![0.0; SR];
+        clean.extend(tone(SR, 1_000.0, 0.1));
+        let noisy: Vec<f32> = clean
+            .iter()
+            .zip(noise(len, 0.1, 2))
+            .map(|(s, n)| s + n)
+            .collect();
+
+        let signal = to_i16(&noisy);
+        let out = run(&signal, 333);
+
+        let window = SR + SR / 10..len;
+        let input_window = &signal[window.clone()];
+        let output_window = &out[window.start + FRAME_LEN..window.end];
+
+        let before = snr_db(input_window, 1_000.0);
+        let after = snr_db(output_window, 1_000.0);
+        assert!(after - before > 10.0, "SNR {before:.1} -> {after:.1} dB");
+
+        let (amplitude, _) = tone_and_residual(output_window, 1_000.0);
+        let loss = 20.0 * (0.1 / amplitude).log10();
+        assert!(loss < 2.0, "tone lost {loss:.1} dB");
+    }
+
+    #[test]
+    fn chunk_size_does_not_change_output() {
+        let signal = to_i16(&noise(SR, 0.2, 3));
+        let expected = run(&signal, SR);
+        for chunk in [1, 100, 256, 1000] {
+            assert_eq!(run(&signal, chunk), expected, "chunk {chunk}");
+        }
+    }
+}
@@ -1,2 +1,5 @@
 pub mod audio;
+pub mod calibration;
+pub mod denoise;
 pub mod text;
+pub mod vad;
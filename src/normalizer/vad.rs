@@ -0,0 +1,339 @@
+use rustfft::{num_complex::Complex, Fft, FftPlanner};
+use std::sync::Arc;
+
+use super::audio::rms_i16;
+
+const FRAME_MS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart,
+    SpeechEnd,
+}
+
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    pub min_rms: f32,
+    pub energy_margin_db: f32,
+    pub max_zcr: f32,
+    pub max_flatness: f32,
+    pub onset_frames: usize,
+    pub hangover_frames: usize,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            min_rms: 0.002,
+            energy_margin_db: 6.0,
+            max_zcr: 0.45,
+            max_flatness: 0.35,
+            onset_frames: 3,
+            hangover_frames: 15,
+        }
+    }
+}
+
+pub struct Vad {
+    config: VadConfig,
+    frame_len: usize,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    pending: Vec<i16>,
+    noise_floor_db: Option<f32>,
+    speech: bool,
+    voiced_run: usize,
+    silent_run: usize,
+}
+
+impl Vad {
+    pub fn new(sample_rate: u32, config: VadConfig) -> Self {
+        let frame_len = (sample_rate * FRAME_MS / 1000).max(16) as usize;
+        let fft = FftPlanner::new().plan_fft_forward(frame_len);
+        let window = (0..frame_len)
+            .map(|i| {
+                let x = i as f32 / (frame_len - 1) as f32;
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * x).cos()
+            })
+            .collect();
+
+        Self {
+            config,
+            frame_len,
+            fft,
+            window,
+            spectrum: vec![Complex::default(); frame_len],
+            pending: Vec::with_capacity(frame_len),
+            noise_floor_db: None,
+            speech: false,
+            voiced_run: 0,
+            silent_run: 0,
+        }
+    }
+
+    pub fn set_min_rms(&mut self, min_rms: f32) {
+        self.config.min_rms = min_rms;
+    }
+
+    pub fn is_speech(&self) -> bool {
+        self.speech
+    }
+
+    pub fn process(&mut self, buf: &[i16]) -> Vec<VadEvent> {
+        let mut events = Vec::new();
+        let mut rest = buf;
+
+        while !rest.is_empty() {
+            let take = (self.frame_len - self.pending.len()).min(rest.len());
+            self.pending.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+
+            if self.pending.len() == self.frame_len {
+                let frame = std::mem::take(&mut self.pending);
+                if let Some(event) = self.process_frame(&frame) {
+                    events.push(event);
+                }
+                self.pending = frame;
+                self.pending.clear();
+            }
+        }
+
+        events
+    }
+
+    fn process_frame(&mut self, frame: &[i16]) -> Option<VadEvent> {
+        let rms = rms_i16(frame);
+        let energy_db = 20.0 * rms.max(1e-6).log10();
+        let floor_db = *self.noise_floor_db.get_or_insert(energy_db);
+
+        let loud =
+            rms >= self.config.min_rms && energy_db > floor_db + self.config.energy_margin_db;
+
+        // Start needs a speech-like spectrum, but once speech is running
+        // energy alone keeps it alive so unvoiced consonants are not cut.
+        let speech_like = loud
+            && zero_crossing_rate(frame) <= self.config.max_zcr
+            && self.spectral_flatness(frame) <= self.config.max_flatness;
+
+        self.track_noise_floor(energy_db, loud);
+
+        if self.speech {
+            if loud {
+                self.silent_run = 0;
+            } else {
+                self.silent_run += 1;
+                if self.silent_run >= self.config.hangover_frames {
+                    self.speech = false;
+                    self.silent_run = 0;
+                    self.voiced_run = 0;
+                    return Some(VadEvent::SpeechEnd);
+                }
+            }
+        } else if speech_like {
+            self.voiced_run += 1;
+            if self.voiced_run >= self.config.onset_frames {
+                self.speech = true;
+                self.voiced_run = 0;
+                self.silent_run = 0;
+                return Some(VadEvent::SpeechStart);
+            }
+        } else {
+            self.voiced_run = 0;
+        }
+
+        None
+    }
+
+    fn track_noise_floor(&mut self, energy_db: f32, loud: bool) {
+        let Some(floor) = self.noise_floor_db.as_mut() else {
+            return;
+        };
+
+        let rate = if energy_db < *floor {
+            0.2
+        } else if loud || self.speech {
+            0.001
+        } else {
+            0.02
+        };
+
+        *floor += rate * (energy_db - *floor);
+    }
+
+    fn spectral_flatness(&mut self, frame: &[i16]) -> f32 {
+        for ((bin, &s), w) in self.spectrum.iter_mut().zip(frame).zip(&self.window) {
+            *bin = Complex::new(s as f32 / i16::MAX as f32 * w, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let bins = &self.spectrum[1..self.frame_len / 2];
+        let mut log_sum = 0.0f32;
+        let mut sum = 0.0f32;
+        for bin in bins {
+            let power = bin.norm_sqr() + 1e-12;
+            log_sum += power.ln();
+            sum += power;
+        }
+
+        let n = bins.len() as f32;
+        (log_sum / n).exp() / (sum / n)
+    }
+}
+
+fn zero_crossing_rate(frame: &[i16]) -> f32 {
+    if frame.len() < 2 {
+        return 0.0;
+    }
+    let crossings = frame
+        .windows(2)
+        .filter(|w| (w[0] >= 0) != (w[1] >= 0))
+        .count();
+    crossings as f32 / (frame.len() - 1) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const SR: u32 = 16_000;
+
+    fn silence(secs: f32) -> Vec<i16> {
+        vec![0; (SR as f32 * secs) as usize]
+    }
+
+    fn noise(secs: f32, amplitude: f32, seed: u64) -> Vec<i16> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..(SR as f32 * secs) as usize)
+            .map(|_| (rng.gen_range(-1.0..1.0) * amplitude * i16::MAX as f32) as i16)
+            .collect()
+    }
+
+    fn vowel(secs: f32, amplitude: f32) -> Vec<i16> {
+        (0..(SR as f32 * secs) as usize)
+            .map(|i| {
+                let t = i as f32 / SR as f32;
+                let mut x = 0.0;
+                for h in 1..=8 {
+                    let f = 140.0 * h as f32;
+                    x += (2.0 * std::f32::consts::PI * f * t).sin() / h as f32;
+                }
+                (x * 0.4 * amplitude * i16::MAX as f32) as i16
+            })
+            .collect()
+    }
+
+    fn mix(a: &[i16], b: &[i16]) -> Vec<i16> {
+        a.iter()
+            .zip(b)
+            .map(|(&x, &y)| x.saturating_add(y))
+            .collect()
+    }
+
+    fn run(vad: &mut Vad, signal: &[i16], chunk: usize) -> Vec<VadEvent> {
+        signal.chunks(chunk).flat_map(|c| vad.process(c)).collect()
+    }
+
+    #[test]
+    fn silence_has_no_events() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        assert!(run(&mut vad, &silence(2.0), 512).is_empty());
+        assert!(!vad.is_speech());
+    }
+
+    #[test]
+    fn voiced_signal_starts_and_ends_speech() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        let mut signal = silence(0.5);
+        signal.extend(vowel(1.0, 0.2));
+        signal.extend(silence(1.0));
+
+        let events = run(&mut vad, &signal, 480);
+        assert_eq!(events, vec![VadEvent::SpeechStart, VadEvent::SpeechEnd]);
+        assert!(!vad.is_speech());
+    }
+
+    #[test]
+    fn speech_start_is_reported_after_onset() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        assert!(run(&mut vad, &silence(0.5), 320).is_empty());
+
+        let speech = vowel(0.2, 0.2);
+        let mut started_at = None;
+        for (i, chunk) in speech.chunks(320).enumerate() {
+            if vad.process(chunk).contains(&VadEvent::SpeechStart) {
+                started_at = Some(i);
+                break;
+            }
+        }
+        assert_eq!(started_at, Some(VadConfig::default().onset_frames - 1));
+    }
+
+    #[test]
+    fn white_noise_is_not_speech() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        let mut signal = silence(0.5);
+        signal.extend(noise(2.0, 0.3, 7));
+        assert!(run(&mut vad, &signal, 512).is_empty());
+    }
+
+    #[test]
+    fn stationary_hum_is_absorbed_by_noise_floor() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        assert!(run(&mut vad, &vowel(3.0, 0.1), 512).is_empty());
+    }
+
+    #[test]
+    fn speech_over_noise_is_detected() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        let background = noise(3.0, 0.01, 3);
+        let mut foreground = silence(1.0);
+        foreground.extend(vowel(1.0, 0.2));
+        foreground.extend(silence(1.0));
+
+        let events = run(&mut vad, &mix(&background, &foreground), 512);
+        assert_eq!(events, vec![VadEvent::SpeechStart, VadEvent::SpeechEnd]);
+    }
+
+    #[test]
+    fn short_click_does_not_start_speech() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        let mut signal = silence(0.5);
+        signal.extend(vowel(0.02, 0.5));
+        signal.extend(silence(0.5));
+        assert!(run(&mut vad, &signal, 320).is_empty());
+    }
+
+    #[test]
+    fn short_pause_is_bridged_by_hangover() {
+        let mut vad = Vad::new(SR, VadConfig::default());
+        let mut signal = silence(0.5);
+        signal.extend(vowel(0.5, 0.2));
+        signal.extend(silence(0.15));
+        signal.extend(vowel(0.5, 0.2));
+
+        let events = run(&mut vad, &signal, 256);
+        assert_eq!(events, vec![VadEvent::SpeechStart]);
+        assert!(vad.is_speech());
+    }
+
+    #[test]
+    fn chunk_size_does_not_change_events() {
+        let mut signal = silence(0.5);
+        signal.extend(vowel(0.8, 0.2));
+        signal.extend(silence(0.8));
+
+        let expected = run(&mut Vad::new(SR, VadConfig::default()), &signal, 320);
+        for chunk in [1, 100, 333, 4096] {
+            let mut vad = Vad::new(SR, VadConfig::default());
+            assert_eq!(run(&mut vad, &signal, chunk), expected, "chunk {chunk}");
+        }
+    }
+
+    #[test]
+    fn zero_crossing_rate_of_alternating_signal() {
+        assert_eq!(zero_crossing_rate(&[1, -1, 1, -1, 1]), 1.0);
+        assert_eq!(zero_crossing_rate(&[1, 2, 3, 4]), 0.0);
+    }
+}
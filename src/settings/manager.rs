@@ -1,7 +1,8 @@
+use anyhow::Result;
 use colored::Colorize;
 use serde_json::{Map, Value};
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::{Read, Write},
     path::Path,
 };
@@ -12,8 +13,26 @@ const DEFAULT_SETTINGS: &str = r#"{
     "text_mode": "false",
     "conversation_mode": "false",
     "language": "uk",
+    "models_dir": "",
+    "persist_language": "false",
     "model": "normal",
-    "fuzzy_matcher_threshold": "0.85"
+    "fuzzy_matcher_threshold": "0.85",
+    "noise_suppression": "false",
+    "wake_words": {
+        "en": [{ "phrase": "aurora" }, { "phrase": "a roar a" }],
+        "uk": [{ "phrase": "аврора" }, { "phrase": "авроро" }]
+    },
+    "wake_threshold": "0.8",
+    "command_grammar": "true",
+    "min_confidence": "0.6",
+    "max_alternatives": "3",
+    "command_window": "6",
+    "timeout_cue": "notification",
+    "input_device": "",
+    "channel_strategy": "average",
+    "calibration_file": "calibration.json",
+    "debug_audio": "false",
+    "debug_audio_dir": "debug_audio"
 }"#;
 
 pub struct SettingsManager {
@@ -66,6 +85,20 @@ impl SettingsManager {
             String::from("")
         }
     }
+    pub fn get_value(&self, setting: &str) -> Option<Value> {
+        self.settings.get(setting).cloned()
+    }
+
+    pub fn set_setting(&mut self, setting: &str, value: &str) -> Result<()> {
+        self.settings
+            .insert(setting.to_string(), Value::String(value.to_string()));
+        fs::write(
+            &self.file_path,
+            serde_json::to_string_pretty(&self.settings)?,
+        )?;
+        Ok(())
+    }
+
     pub fn print_settings(&self) {
         println!("{}", "[*] Settings".bold().magenta());
         for setting in self.settings.keys() {
@@ -90,6 +123,29 @@ mod tests {
         assert_ne!(text_mode, "");
     }
 
+    #[test]
+    fn test_set_setting_is_saved() {
+        let path = crate::testing::temp_path("settings.json");
+        let path = path.to_string_lossy().to_string();
+
+        let keys = |path: &str| -> Vec<String> {
+            let contents = std::fs::read_to_string(path).unwrap();
+            let settings: super::JsonMap = serde_json::from_str(&contents).unwrap();
+            settings.keys().cloned().collect()
+        };
+
+        let mut settings_manager = SettingsManager::new(path.clone());
+        assert_eq!(settings_manager.get_setting("language"), "uk");
+        let before = keys(&path);
+        settings_manager.set_setting("language", "en").unwrap();
+        assert_eq!(keys(&path), before);
+
+        let settings_manager = SettingsManager::new(path.clone());
+        assert_eq!(settings_manager.get_setting("language"), "en");
+        assert_ne!(settings_manager.get_setting("text_mode"), "");
+        std::fs::remove_file(path).unwrap();
+    }
+
     #[test]
     fn test_get_setting_invalid() {
         let settings_manager = SettingsManager::new(String::from(SETTINGS_FILE_PATH));
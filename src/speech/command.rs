@@ -0,0 +1,559 @@
+use anyhow::Result;
+
+use super::{recognizer::SpeechRecognizer, Alternative, Transcript};
+use crate::commands::{parser::parse_command, Command};
+
+const EARLY_STABLE_MS: usize = 200;
+
+// Runs the full recognizer next to one limited to the parser's vocabulary and
+// prefers the latter unless the command needs open vocabulary.
+pub struct CommandRecognizer {
+    full: Box<dyn SpeechRecognizer>,
+    biased: Option<Box<dyn SpeechRecognizer>>,
+    // Vosk drops a result that is not read before the next chunk, and the
+    // grammar recognizer often endpoints a chunk before the full one.
+    biased_result: Option<Transcript>,
+    last_pick: Option<Pick>,
+}
+
+impl CommandRecognizer {
+    pub fn new(full: Box<dyn SpeechRecognizer>, biased: Option<Box<dyn SpeechRecognizer>>) -> Self {
+        Self {
+            full,
+            biased,
+            biased_result: None,
+            last_pick: None,
+        }
+    }
+
+    // How the open-vocabulary result was picked from the n-best list.
+    pub fn last_pick(&self) -> Option<&Pick> {
+        self.last_pick.as_ref()
+    }
+
+    pub fn accept(&mut self, audio: &[i16], end_of_speech: bool) -> Result<Option<Transcript>> {
+        let endpoint = self.full.accept_audio(audio)?;
+        if let Some(biased) = self.biased.as_mut() {
+            if biased.accept_audio(audio)? {
+                let result = first(biased.result());
+                if !result.text.is_empty() {
+                    self.biased_result = Some(result);
+                }
+            }
+        }
+
+        let full = if endpoint {
+            self.full.result()
+        } else if end_of_speech {
+            self.full.final_result()
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(self.transcribe(full)))
+    }
+
+    // Forces a result for the audio fed so far.
+    pub fn finish(&mut self) -> Transcript {
+        let full = self.full.final_result();
+        self.transcribe(full)
+    }
+
+    fn transcribe(&mut self, full: Vec<Alternative>) -> Transcript {
+        self.last_pick = pick(full);
+        let full = self
+            .last_pick
+            .as_ref()
+            .map(|p| p.transcript.clone())
+            .unwrap_or_default();
+        let early = self.biased_result.take();
+        let biased = self
+            .biased
+            .as_mut()
+            .map(|biased| early.unwrap_or_else(|| first(biased.final_result())));
+
+        choose(full, biased)
+    }
+
+    pub fn partial(&mut self) -> Transcript {
+        if let Some(biased) = self.biased.as_mut() {
+            let partial = biased.partial();
+            if !partial.text.is_empty() && !partial.text.contains("[unk]") {
+                return partial;
+            }
+        }
+        self.full.partial()
+    }
+
+    pub fn reset(&mut self) {
+        self.biased_result = None;
+        self.last_pick = None;
+        self.full.reset();
+        if let Some(biased) = self.biased.as_mut() {
+            biased.reset();
+        }
+    }
+}
+
+fn first(alternatives: Vec<Alternative>) -> Transcript {
+    alternatives
+        .into_iter()
+        .next()
+        .map(|alt| alt.transcript)
+        .unwrap_or_default()
+}
+
+// Fires an instant command once its partial parse has been stable and
+// confident for a while, and remembers it so the final result does not run
+// it again.
+pub struct EarlyCommand {
+    stable_samples: usize,
+    min_confidence: f32,
+    candidate: Option<Command>,
+    stable: usize,
+    fired: Option<Command>,
+}
+
+impl EarlyCommand {
+    pub fn new(sample_rate: u32, min_confidence: f32) -> Self {
+        Self {
+            stable_samples: sample_rate as usize * EARLY_STABLE_MS / 1000,
+            min_confidence,
+            candidate: None,
+            stable: 0,
+            fired: None,
+        }
+    }
+
+    pub fn update(&mut self, partial: &Transcript, samples: usize) -> Option<Command> {
+        if self.fired.is_some() || partial.text.is_empty() {
+            return None;
+        }
+
+        let cmd = parse_command(&partial.text);
+        let confident = self.min_confidence <= 0.0
+            || partial
+                .confidence()
+                .is_some_and(|conf| conf >= self.min_confidence);
+        if !cmd.is_instant() || !confident {
+            self.candidate = None;
+            self.stable = 0;
+            return None;
+        }
+
+        if self.candidate.as_ref() == Some(&cmd) {
+            self.stable += samples;
+        } else {
+            self.candidate = Some(cmd);
+            self.stable = 0;
+        }
+
+        if self.stable < self.stable_samples {
+            return None;
+        }
+        self.fired = self.candidate.take();
+        self.fired.clone()
+    }
+
+    // True when the final command already ran from a partial result.
+    pub fn finish(&mut self, cmd: &Command) -> bool {
+        let fired = self.fired.take();
+        self.reset();
+        fired.is_some_and(|fired| &fired == cmd)
+    }
+
+    pub fn reset(&mut self) {
+        self.candidate = None;
+        self.stable = 0;
+        self.fired = None;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pick {
+    pub transcript: Transcript,
+    pub command: Command,
+    pub rank: usize,
+    pub weight: f32,
+    // Weight of all alternatives that parse to the same command.
+    pub score: f32,
+    pub total: usize,
+    // Higher ranked alternatives that did not parse.
+    pub skipped: Vec<String>,
+}
+
+impl Pick {
+    pub fn reason(&self) -> String {
+        let mut reason = format!(
+            "alternative {}/{} (weight {:.2}) -> {:?}",
+            self.rank, self.total, self.weight, self.command
+        );
+        if !self.skipped.is_empty() {
+            let skipped: Vec<String> = self.skipped.iter().map(|t| format!("\"{t}\"")).collect();
+            reason.push_str(&format!(
+                ", outranked {} which did not parse",
+                skipped.join(", ")
+            ));
+        }
+        reason
+    }
+}
+
+// Takes the best weighted alternative that maps to a command, or the top one
+// when none of them does.
+pub fn pick(alternatives: Vec<Alternative>) -> Option<Pick> {
+    let total = alternatives.len();
+    if total == 0 {
+        return None;
+    }
+    let mut parsed: Vec<(usize, Alternative, Command)> = alternatives
+        .into_iter()
+        .enumerate()
+        .map(|(i, alt)| {
+            let cmd = parse_command(&alt.transcript.text);
+            (i, alt, cmd)
+        })
+        .collect();
+
+    let best = parsed
+        .iter()
+        .filter(|(_, alt, cmd)| {
+            !alt.transcript.text.is_empty() && !matches!(cmd, Command::Unknown(_))
+        })
+        .max_by(|a, b| a.1.weight.total_cmp(&b.1.weight).then(b.0.cmp(&a.0)))
+        .map(|(i, _, _)| *i)
+        .unwrap_or(0);
+
+    let skipped = parsed[..best]
+        .iter()
+        .map(|(_, alt, _)| alt.transcript.text.clone())
+        .collect();
+    let score = parsed
+        .iter()
+        .filter(|(_, _, cmd)| *cmd == parsed[best].2)
+        .map(|(_, alt, _)| alt.weight)
+        .sum();
+    let (rank, alt, command) = parsed.swap_remove(best);
+    Some(Pick {
+        transcript: alt.transcript,
+        command,
+        rank: rank + 1,
+        weight: alt.weight,
+        score,
+        total,
+        skipped,
+    })
+}
+
+pub fn choose(full: Transcript, biased: Option<Transcript>) -> Transcript {
+    let Some(biased) = biased else {
+        return full;
+    };
+    if biased.text.is_empty() || biased.text.contains("[unk]") {
+        return full;
+    }
+
+    match parse_command(&biased.text) {
+        Command::Unknown(_) => full,
+        cmd if cmd.is_free_form() => full,
+        _ => biased,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{speech::scripted::ScriptedRecognizer, testing::scored};
+
+    fn transcript(text: &str) -> Transcript {
+        Transcript {
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn biased_result_wins_for_known_commands() {
+        let chosen = choose(
+            transcript("відкрий обсидіан"),
+            Some(transcript("відкрий обсідіан")),
+        );
+        assert_eq!(chosen.text, "відкрий обсідіан");
+    }
+
+    #[test]
+    fn free_form_slots_use_open_vocabulary() {
+        let chosen = choose(
+            transcript("знайди рецепт борщу"),
+            Some(transcript("знайди [unk]")),
+        );
+        assert_eq!(chosen.text, "знайди рецепт борщу");
+
+        let chosen = choose(
+            transcript("dictate hello world"),
+            Some(transcript("dictate")),
+        );
+        assert_eq!(chosen.text, "dictate hello world");
+    }
+
+    #[test]
+    fn unknown_or_empty_biased_result_is_ignored() {
+        let chosen = choose(transcript("зроби чай"), Some(transcript("")));
+        assert_eq!(chosen.text, "зроби чай");
+
+        let chosen = choose(transcript("hello there"), Some(transcript("help")));
+        assert_eq!(chosen.text, "hello there");
+    }
+
+    #[test]
+    fn early_command_needs_a_stable_partial() {
+        let mut early = EarlyCommand::new(16_000, 0.6);
+        assert_eq!(early.update(&scored("наступна", 0.9), 1_600), None);
+        assert_eq!(early.update(&scored("наступна", 0.9), 1_600), None);
+        assert_eq!(
+            early.update(&scored("наступна пісня", 0.9), 1_600),
+            Some(Command::AudioNext)
+        );
+        assert_eq!(early.update(&scored("наступна пісня", 0.9), 1_600), None);
+    }
+
+    #[test]
+    fn changing_partial_restarts_the_wait() {
+        let mut early = EarlyCommand::new(16_000, 0.6);
+        early.update(&scored("гучність більше", 0.9), 3_000);
+        assert_eq!(early.update(&scored("гучність менше", 0.9), 3_000), None);
+        assert_eq!(
+            early.update(&scored("гучність менше", 0.9), 3_200),
+            Some(Command::VolumeDown)
+        );
+    }
+
+    #[test]
+    fn unsure_partial_never_fires_early() {
+        let mut early = EarlyCommand::new(16_000, 0.6);
+        for _ in 0..5 {
+            assert_eq!(early.update(&scored("пауза", 0.4), 3_200), None);
+            assert_eq!(early.update(&transcript("пауза"), 3_200), None);
+        }
+
+        let mut early = EarlyCommand::new(16_000, 0.0);
+        early.update(&transcript("пауза"), 3_200);
+        assert_eq!(
+            early.update(&transcript("пауза"), 3_200),
+            Some(Command::AudioPause)
+        );
+    }
+
+    #[test]
+    fn slot_commands_never_fire_early() {
+        let mut early = EarlyCommand::new(16_000, 0.6);
+        for _ in 0..5 {
+            assert_eq!(early.update(&scored("знайди погода", 0.9), 3_200), None);
+            assert_eq!(early.update(&scored("вимкни wifi", 0.9), 3_200), None);
+        }
+    }
+
+    #[test]
+    fn final_result_does_not_run_twice() {
+        let mut early = EarlyCommand::new(16_000, 0.6);
+        early.update(&scored("pause", 0.9), 3_200);
+        assert_eq!(
+            early.update(&scored("pause", 0.9), 3_200),
+            Some(Command::AudioPause)
+        );
+        assert!(early.finish(&Command::AudioPause));
+        assert!(!early.finish(&Command::AudioPause));
+
+        early.update(&scored("next", 0.9), 3_200);
+        early.update(&scored("next", 0.9), 3_200);
+        assert!(!early.finish(&Command::OpenApp(crate::commands::App::Firefox)));
+
+        early.update(&scored("next", 0.9), 3_200);
+        early.update(&scored("next", 0.9), 3_200);
+        assert!(!early.finish(&Command::Unknown(String::from("next"))));
+    }
+
+    fn alternative(text: &str, weight: f32) -> Alternative {
+        Alternative {
+            transcript: transcript(text),
+            weight,
+        }
+    }
+
+    // Words of an n-best list, as produced by `alternatives`.
+    fn unscored(text: &str, weight: f32) -> Alternative {
+        Alternative {
+            transcript: Transcript {
+                text: text.to_string(),
+                words: text
+                    .split_whitespace()
+                    .map(|word| crate::speech::Word {
+                        text: word.to_string(),
+                        start: 0.0,
+                        end: 0.0,
+                        conf: None,
+                    })
+                    .collect(),
+            },
+            weight,
+        }
+    }
+
+    #[test]
+    fn n_best_pick_is_scored_by_agreeing_weight() {
+        use crate::speech::confidence::{check, Verdict};
+
+        let picked = pick(vec![
+            unscored("pause", 0.4),
+            unscored("pause please", 0.3),
+            unscored("hello there", 0.3),
+        ])
+        .unwrap();
+        assert_eq!(picked.rank, 1);
+        assert!((picked.score - 0.7).abs() < 1e-6);
+        assert_eq!(
+            check(&picked.command, &picked.transcript, Some(&picked), 0.6),
+            Verdict::Accept
+        );
+    }
+
+    #[test]
+    fn lower_ranked_pick_is_not_accepted_blindly() {
+        use crate::speech::confidence::{check, Verdict};
+
+        let picked = pick(vec![
+            unscored("зроби чай", 0.5),
+            unscored("наступна пісня", 0.3),
+            unscored("пауза", 0.2),
+        ])
+        .unwrap();
+        assert_eq!(picked.rank, 2);
+        assert_eq!(
+            check(&picked.command, &picked.transcript, Some(&picked), 0.6),
+            Verdict::Reject(0.3)
+        );
+
+        // Even a well supported destructive command asks first.
+        let picked = pick(vec![
+            unscored("hello there", 0.3),
+            unscored("shutdown computer", 0.4),
+            unscored("shutdown the computer", 0.3),
+        ])
+        .unwrap();
+        assert_eq!(picked.command, Command::Poweroff);
+        assert_eq!(picked.rank, 2);
+        let Verdict::Confirm(conf) = check(&picked.command, &picked.transcript, Some(&picked), 0.6)
+        else {
+            panic!("destructive pick was not confirmed");
+        };
+        assert!((conf - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pick_skips_alternatives_that_do_not_parse() {
+        let picked = pick(vec![
+            alternative("зроби чай", 0.5),
+            alternative("наступна пісня", 0.3),
+            alternative("пауза", 0.2),
+        ])
+        .unwrap();
+        assert_eq!(picked.transcript.text, "наступна пісня");
+        assert_eq!(picked.command, Command::AudioNext);
+        assert_eq!(picked.rank, 2);
+        assert_eq!(picked.skipped, vec!["зроби чай".to_string()]);
+    }
+
+    #[test]
+    fn pick_falls_back_to_the_top_alternative() {
+        let picked = pick(vec![
+            alternative("зроби чай", 0.7),
+            alternative("зроби час", 0.3),
+        ])
+        .unwrap();
+        assert_eq!(picked.rank, 1);
+        assert!(matches!(picked.command, Command::Unknown(_)));
+        assert!(picked.skipped.is_empty());
+        assert!(pick(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn pick_reason_names_the_winner() {
+        let picked = pick(vec![alternative("hello", 0.6), alternative("pause", 0.4)]).unwrap();
+        assert_eq!(
+            picked.reason(),
+            "alternative 2/2 (weight 0.40) -> AudioPause, outranked \"hello\" which did not parse"
+        );
+    }
+
+    #[test]
+    fn recognizer_prefers_the_biased_result() {
+        let full = ScriptedRecognizer::new(1_000.0).result_at(0.5, transcript("відкрий обсидіан"));
+        let biased =
+            ScriptedRecognizer::new(1_000.0).result_at(0.5, transcript("відкрий обсідіан"));
+        let mut rec = CommandRecognizer::new(Box::new(full), Some(Box::new(biased)));
+
+        assert!(rec.accept(&[0; 400], false).unwrap().is_none());
+        let t = rec.accept(&[0; 200], false).unwrap().unwrap();
+        assert_eq!(t.text, "відкрий обсідіан");
+        assert_eq!(rec.last_pick().unwrap().transcript.text, "відкрий обсидіан");
+    }
+
+    #[test]
+    fn biased_endpoint_before_the_full_one_is_kept() {
+        let full = ScriptedRecognizer::new(1_000.0).result_at(0.6, transcript("відкрий обсидіан"));
+        let biased =
+            ScriptedRecognizer::new(1_000.0).result_at(0.5, transcript("відкрий обсідіан"));
+        let mut rec = CommandRecognizer::new(Box::new(full), Some(Box::new(biased)));
+
+        assert!(rec.accept(&[0; 500], false).unwrap().is_none());
+        let t = rec.accept(&[0; 100], false).unwrap().unwrap();
+        assert_eq!(t.text, "відкрий обсідіан");
+
+        rec.reset();
+        assert!(rec.biased_result.is_none());
+    }
+
+    #[test]
+    fn end_of_speech_flushes_the_recognizer() {
+        let full = ScriptedRecognizer::new(1_000.0).partial_at(0.1, transcript("пауза"));
+        let mut rec = CommandRecognizer::new(Box::new(full), None);
+
+        assert!(rec.accept(&[0; 200], false).unwrap().is_none());
+        assert_eq!(rec.partial().text, "пауза");
+        let t = rec.accept(&[0; 100], true).unwrap().unwrap();
+        assert!(t.text.is_empty());
+    }
+
+    fn run_with_early(conf: f32) -> (Vec<Command>, bool) {
+        let full = ScriptedRecognizer::new(1_000.0)
+            .partial_at(0.1, scored("наступна", conf))
+            .partial_at(0.2, scored("наступна пісня", conf))
+            .result_at(1.0, scored("наступна пісня", conf));
+        let mut rec = CommandRecognizer::new(Box::new(full), None);
+        let mut early = EarlyCommand::new(1_000, 0.6);
+
+        let mut fired = Vec::new();
+        let last = loop {
+            match rec.accept(&[0; 100], false).unwrap() {
+                Some(t) => break t,
+                None => fired.extend(early.update(&rec.partial(), 100)),
+            }
+        };
+        (fired, early.finish(&parse_command(&last.text)))
+    }
+
+    #[test]
+    fn recognizer_partials_fire_early_once() {
+        let (fired, already_ran) = run_with_early(0.9);
+        assert_eq!(fired, vec![Command::AudioNext]);
+        assert!(already_ran);
+
+        let (fired, already_ran) = run_with_early(0.3);
+        assert!(fired.is_empty());
+        assert!(!already_ran);
+    }
+
+    #[test]
+    fn without_grammar_full_result_is_used() {
+        assert_eq!(choose(transcript("pause"), None).text, "pause");
+    }
+}
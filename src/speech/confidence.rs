@@ -0,0 +1,102 @@
+use super::{command::Pick, Transcript};
+use crate::commands::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Accept,
+    Reject(f32),
+    Confirm(f32),
+}
+
+// Vosk only scores words of a single best result. For an n-best list the
+// score is the share of the weight that agrees on the command, and a command
+// promoted over higher ranked alternatives never runs destructive ones unasked.
+pub fn check(
+    cmd: &Command,
+    transcript: &Transcript,
+    pick: Option<&Pick>,
+    min_confidence: f32,
+) -> Verdict {
+    if matches!(cmd, Command::Unknown(_)) {
+        return Verdict::Accept;
+    }
+
+    let pick = pick.filter(|p| p.transcript.text == transcript.text);
+    let conf = transcript
+        .confidence()
+        .or(pick.map(|p| p.score))
+        .unwrap_or(0.0);
+    let promoted = pick.is_some_and(|p| p.rank > 1);
+
+    if cmd.is_destructive() && (conf < min_confidence || promoted) {
+        Verdict::Confirm(conf)
+    } else if conf < min_confidence {
+        Verdict::Reject(conf)
+    } else {
+        Verdict::Accept
+    }
+}
+
+pub fn word_scores(transcript: &Transcript) -> String {
+    transcript
+        .words
+        .iter()
+        .map(|w| match w.conf {
+            Some(conf) => format!("{}={:.2}", w.text, conf),
+            None => format!("{}=?", w.text),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::scored_words;
+
+    #[test]
+    fn confident_command_is_accepted() {
+        let t = scored_words(&[("наступна", 0.9), ("пісня", 0.8)]);
+        assert_eq!(check(&Command::AudioNext, &t, None, 0.6), Verdict::Accept);
+    }
+
+    #[test]
+    fn low_confidence_command_is_rejected() {
+        let t = scored_words(&[("наступна", 0.5), ("пісня", 0.3)]);
+        assert_eq!(
+            check(&Command::AudioNext, &t, None, 0.6),
+            Verdict::Reject(0.4)
+        );
+    }
+
+    #[test]
+    fn low_confidence_destructive_command_needs_confirmation() {
+        let t = scored_words(&[("вимкни", 0.5), ("комп'ютер", 0.5)]);
+        assert_eq!(
+            check(&Command::Poweroff, &t, None, 0.6),
+            Verdict::Confirm(0.5)
+        );
+    }
+
+    #[test]
+    fn unscored_command_is_not_accepted() {
+        let t = Transcript {
+            text: String::from("pause"),
+            words: Vec::new(),
+        };
+        assert_eq!(
+            check(&Command::AudioPause, &t, None, 0.6),
+            Verdict::Reject(0.0)
+        );
+        assert_eq!(
+            check(&Command::Poweroff, &t, None, 0.6),
+            Verdict::Confirm(0.0)
+        );
+    }
+
+    #[test]
+    fn scores_are_listed_per_word() {
+        let t = scored_words(&[("open", 0.314), ("firefox", 0.5)]);
+        assert_eq!(word_scores(&t), "open=0.31 firefox=0.50");
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::Result;
+
+use super::{
+    command::{CommandRecognizer, Pick},
+    Transcript,
+};
+use crate::commands::{parser::parse_command, Command};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Ukrainian,
+}
+
+impl Language {
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Ukrainian => "uk",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Language::English),
+            "uk" => Some(Language::Ukrainian),
+            _ => None,
+        }
+    }
+}
+
+// Feeds the same audio to one recognizer per language and keeps the result
+// that parses, or failing that the more confident one.
+pub struct MultiRecognizer {
+    recs: Vec<(Language, CommandRecognizer)>,
+    chosen: usize,
+    reason: &'static str,
+}
+
+impl MultiRecognizer {
+    pub fn new(recs: Vec<(Language, CommandRecognizer)>) -> Self {
+        assert!(!recs.is_empty(), "at least one recognizer is required");
+        Self {
+            recs,
+            chosen: 0,
+            reason: "",
+        }
+    }
+
+    pub fn is_multilingual(&self) -> bool {
+        self.recs.len() > 1
+    }
+
+    // Language of the last chosen result.
+    pub fn language(&self) -> Language {
+        self.recs[self.chosen].0
+    }
+
+    // Why the last language won.
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    pub fn last_pick(&self) -> Option<&Pick> {
+        self.recs[self.chosen].1.last_pick()
+    }
+
+    pub fn accept(&mut self, audio: &[i16], end_of_speech: bool) -> Result<Option<Transcript>> {
+        let mut results = Vec::with_capacity(self.recs.len());
+        for (_, rec) in self.recs.iter_mut() {
+            results.push(rec.accept(audio, end_of_speech)?);
+        }
+        if results.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+
+        // The first endpoint ends the utterance for every language.
+        let results: Vec<(Language, Transcript)> = results
+            .into_iter()
+            .zip(self.recs.iter_mut())
+            .map(|(result, (lang, rec))| (*lang, result.unwrap_or_else(|| rec.finish())))
+            .collect();
+
+        (self.chosen, self.reason) = choose_language(&results);
+        let chosen = self.chosen;
+        Ok(results.into_iter().nth(chosen).map(|(_, t)| t))
+    }
+
+    pub fn partial(&mut self) -> Transcript {
+        let mut partials: Vec<Transcript> =
+            self.recs.iter_mut().map(|(_, rec)| rec.partial()).collect();
+        let best = partials
+            .iter()
+            .position(|p| {
+                !p.text.is_empty() && !matches!(parse_command(&p.text), Command::Unknown(_))
+            })
+            .unwrap_or(0);
+        partials.swap_remove(best)
+    }
+
+    pub fn reset(&mut self) {
+        for (_, rec) in self.recs.iter_mut() {
+            rec.reset();
+        }
+    }
+}
+
+// Returns the index of the preferred result and a short explanation.
+pub fn choose_language(results: &[(Language, Transcript)]) -> (usize, &'static str) {
+    let parses = |t: &Transcript| {
+        !t.text.is_empty() && !matches!(parse_command(&t.text), Command::Unknown(_))
+    };
+    // An unscored result (e.g. from an n-best list) counts as a coin flip.
+    let confidence = |t: &Transcript| {
+        if t.text.is_empty() {
+            0.0
+        } else {
+            t.confidence().unwrap_or(0.5)
+        }
+    };
+    let best = |candidates: Vec<usize>| {
+        candidates.into_iter().reduce(|a, b| {
+            if confidence(&results[b].1) > confidence(&results[a].1) {
+                b
+            } else {
+                a
+            }
+        })
+    };
+
+    let parsed: Vec<usize> = (0..results.len())
+        .filter(|&i| parses(&results[i].1))
+        .collect();
+    match parsed.len() {
+        0 => (
+            best((0..results.len()).collect()).unwrap_or(0),
+            "higher confidence",
+        ),
+        1 => (parsed[0], "only one parsed as a command"),
+        _ => (best(parsed).unwrap_or(0), "both parsed, higher confidence"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{speech::scripted::ScriptedRecognizer, testing::scored};
+
+    #[test]
+    fn language_codes_round_trip() {
+        for lang in [Language::English, Language::Ukrainian] {
+            assert_eq!(Language::from_code(lang.code()), Some(lang));
+        }
+        assert_eq!(Language::from_code("bilingual"), None);
+    }
+
+    #[test]
+    fn parsed_result_beats_a_more_confident_one() {
+        let results = vec![
+            (Language::English, scored("next song", 0.5)),
+            (Language::Ukrainian, scored("нехай сон", 0.9)),
+        ];
+        assert_eq!(choose_language(&results).0, 0);
+    }
+
+    #[test]
+    fn confidence_breaks_ties() {
+        let both = vec![
+            (Language::English, scored("pause", 0.4)),
+            (Language::Ukrainian, scored("пауза", 0.8)),
+        ];
+        assert_eq!(choose_language(&both).0, 1);
+
+        let neither = vec![
+            (Language::English, scored("hello there", 0.9)),
+            (Language::Ukrainian, scored("хело зе", 0.3)),
+        ];
+        assert_eq!(choose_language(&neither).0, 0);
+    }
+
+    #[test]
+    fn unscored_result_is_neutral() {
+        let mut unscored = scored("pause", 0.0);
+        for word in unscored.words.iter_mut() {
+            word.conf = None;
+        }
+
+        let results = vec![
+            (Language::English, unscored.clone()),
+            (Language::Ukrainian, scored("пауза", 0.8)),
+        ];
+        assert_eq!(choose_language(&results).0, 1);
+
+        let results = vec![
+            (Language::English, unscored),
+            (Language::Ukrainian, scored("пауза", 0.3)),
+        ];
+        assert_eq!(choose_language(&results).0, 0);
+    }
+
+    #[test]
+    fn first_endpoint_finishes_every_language() {
+        let en = ScriptedRecognizer::new(1_000.0).result_at(0.5, scored("next song", 0.9));
+        let uk = ScriptedRecognizer::new(1_000.0).result_at(1.0, scored("нехай сон", 0.9));
+        let mut rec = MultiRecognizer::new(vec![
+            (
+                Language::English,
+                CommandRecognizer::new(Box::new(en), None),
+            ),
+            (
+                Language::Ukrainian,
+                CommandRecognizer::new(Box::new(uk), None),
+            ),
+        ]);
+
+        let t = rec.accept(&[0; 600], false).unwrap().unwrap();
+        assert_eq!(t.text, "next song");
+        assert_eq!(rec.language(), Language::English);
+        assert!(rec.is_multilingual());
+    }
+
+    #[test]
+    fn empty_result_loses() {
+        let results = vec![
+            (Language::English, scored("", 1.0)),
+            (Language::Ukrainian, scored("привіт", 0.2)),
+        ];
+        assert_eq!(choose_language(&results).0, 1);
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde_json::Value;
+use std::{
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+use vosk::{Model, Recognizer};
+
+use super::{
+    command::CommandRecognizer, language::Language, models::ModelInfo, recognizer::SpeechRecognizer,
+};
+use crate::{
+    commands::parser::vocabulary,
+    wake::{WakeDetector, WakeListener},
+};
+
+#[derive(Debug, Clone)]
+pub struct LoaderConfig {
+    pub sample_rate: f32,
+    pub max_alternatives: u16,
+    pub command_grammar: bool,
+    pub wake_words: Option<Value>,
+    pub wake_threshold: f64,
+}
+
+// Everything one language needs, built off the audio thread.
+pub struct LoadedLanguage {
+    pub language: Language,
+    full: Recognizer,
+    biased: Option<Recognizer>,
+    wake_rec: Recognizer,
+    wake: WakeDetector,
+    sample_rate: f32,
+}
+
+impl LoadedLanguage {
+    pub fn into_parts(self) -> (CommandRecognizer, WakeListener) {
+        let rec = CommandRecognizer::new(
+            Box::new(self.full),
+            self.biased
+                .map(|rec| Box::new(rec) as Box<dyn SpeechRecognizer>),
+        );
+        let listener = WakeListener::new(Box::new(self.wake_rec), self.wake, self.sample_rate);
+        (rec, listener)
+    }
+}
+
+pub fn load(language: Language, info: &ModelInfo, config: &LoaderConfig) -> Result<LoadedLanguage> {
+    println!(
+        "{}{}",
+        format!("[*] Model ({}): ", language.code())
+            .magenta()
+            .bold(),
+        info.path.display().to_string().magenta()
+    );
+    let model = Model::new(info.path.to_string_lossy())
+        .with_context(|| format!("Unable to load Vosk model {}", info.path.display()))?;
+
+    let mut full = Recognizer::new(&model, config.sample_rate).context("Recognizer::new failed")?;
+    full.set_words(true);
+    full.set_partial_words(true);
+    if config.max_alternatives > 1 {
+        full.set_max_alternatives(config.max_alternatives);
+    }
+
+    let biased = if config.command_grammar {
+        let mut grammar = vocabulary();
+        grammar.push(String::from("[unk]"));
+        let mut biased = Recognizer::new_with_grammar(&model, config.sample_rate, &grammar);
+        if let Some(biased) = biased.as_mut() {
+            biased.set_words(true);
+            biased.set_partial_words(true);
+        } else {
+            println!(
+                "{}",
+                "[!] Unable to build the command grammar, using open vocabulary only".yellow()
+            );
+        }
+        biased
+    } else {
+        None
+    };
+
+    let wake = WakeDetector::from_settings(
+        config.wake_words.clone(),
+        language.code(),
+        config.wake_threshold,
+    );
+    println!(
+        "{}{}",
+        format!("[*] Wake words ({}): ", language.code())
+            .magenta()
+            .bold(),
+        wake.phrases().collect::<Vec<_>>().join(", ").magenta()
+    );
+
+    // A tiny grammar keeps the idle phase cheap; the full model only runs
+    // inside the command window.
+    let mut wake_rec =
+        match Recognizer::new_with_grammar(&model, config.sample_rate, &wake.grammar()) {
+            Some(wake_rec) => wake_rec,
+            None => {
+                println!(
+                    "{}",
+                    "[!] Unable to build the wake word grammar, listening with the full model"
+                        .yellow()
+                );
+                Recognizer::new(&model, config.sample_rate).context("Recognizer::new failed")?
+            }
+        };
+    wake_rec.set_words(true);
+
+    Ok(LoadedLanguage {
+        language,
+        full,
+        biased,
+        wake_rec,
+        wake,
+        sample_rate: config.sample_rate,
+    })
+}
+
+pub fn load_in_background(
+    language: Language,
+    info: ModelInfo,
+    config: LoaderConfig,
+) -> Receiver<Result<LoadedLanguage>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(load(language, &info, &config));
+    });
+    rx
+}
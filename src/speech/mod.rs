@@ -0,0 +1,121 @@
+pub mod command;
+pub mod confidence;
+pub mod language;
+pub mod loader;
+pub mod models;
+pub mod recognizer;
+#[cfg(test)]
+pub mod scripted;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    // Vosk only scores the words of a single best result, not of n-best lists.
+    pub conf: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub text: String,
+    pub words: Vec<Word>,
+}
+
+impl Transcript {
+    pub fn confidence(&self) -> Option<f32> {
+        if self.words.is_empty() {
+            return None;
+        }
+        let confs: Option<Vec<f32>> = self.words.iter().map(|w| w.conf).collect();
+        confs.map(|confs| confs.iter().sum::<f32>() / confs.len() as f32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alternative {
+    pub transcript: Transcript,
+    pub weight: f32,
+}
+
+// Vosk scores alternatives on an unbounded log scale; turn them into weights
+// that sum to one. The weight ranks alternatives, it is not word confidence.
+pub fn alternatives(res: vosk::CompleteResult<'_>) -> Vec<Alternative> {
+    match res {
+        vosk::CompleteResult::Single(single) => vec![Alternative {
+            transcript: Transcript {
+                text: single.text.to_string(),
+                words: single
+                    .result
+                    .iter()
+                    .map(|w| Word {
+                        text: w.word.to_string(),
+                        start: w.start,
+                        end: w.end,
+                        conf: Some(w.conf),
+                    })
+                    .collect(),
+            },
+            weight: 1.0,
+        }],
+        vosk::CompleteResult::Multiple(multiple) => {
+            let scores: Vec<f32> = multiple.alternatives.iter().map(|a| a.confidence).collect();
+            multiple
+                .alternatives
+                .iter()
+                .zip(weights(&scores))
+                .map(|(alt, weight)| Alternative {
+                    transcript: Transcript {
+                        text: alt.text.to_string(),
+                        words: alt
+                            .result
+                            .iter()
+                            .map(|w| Word {
+                                text: w.word.to_string(),
+                                start: w.start,
+                                end: w.end,
+                                conf: None,
+                            })
+                            .collect(),
+                    },
+                    weight,
+                })
+                .collect()
+        }
+    }
+}
+
+fn weights(scores: &[f32]) -> Vec<f32> {
+    let best = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exp: Vec<f32> = scores.iter().map(|s| (s - best).exp()).collect();
+    let total: f32 = exp.iter().sum();
+    exp.iter().map(|e| e / total).collect()
+}
+
+impl From<vosk::CompleteResult<'_>> for Transcript {
+    fn from(res: vosk::CompleteResult<'_>) -> Self {
+        alternatives(res)
+            .into_iter()
+            .next()
+            .map(|alt| alt.transcript)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_sum_to_one_and_keep_order() {
+        let w = weights(&[230.0, 229.0, 225.0]);
+        assert!((w.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(w[0] > w[1] && w[1] > w[2]);
+        assert!((w[0] / w[1] - 1f32.exp()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn equal_scores_share_the_weight() {
+        assert_eq!(weights(&[10.0, 10.0]), vec![0.5, 0.5]);
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::language::Language;
+
+// Where models lived before the models directory became configurable.
+const LEGACY_DIR: &str = "models/stt";
+const REQUIRED_FILES: &[&str] = &["am/final.mdl", "conf/mfcc.conf"];
+// Either a static graph or a lookahead pair.
+const GRAPHS: &[&[&str]] = &[&["graph/HCLG.fst"], &["graph/HCLr.fst", "graph/Gr.fst"]];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ModelSize {
+    Nano,
+    Small,
+    Normal,
+}
+
+impl ModelSize {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "nano" => Some(ModelSize::Nano),
+            "small" => Some(ModelSize::Small),
+            "normal" => Some(ModelSize::Normal),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ModelSize::Nano => "nano",
+            ModelSize::Small => "small",
+            ModelSize::Normal => "normal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelInfo {
+    pub path: PathBuf,
+    pub language: Option<Language>,
+    pub size: ModelSize,
+    pub sample_rate: Option<u32>,
+    pub bytes: u64,
+}
+
+pub fn default_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("aurora").join("models"))
+}
+
+pub fn search_dirs(configured: &str) -> Vec<PathBuf> {
+    if !configured.trim().is_empty() {
+        return vec![PathBuf::from(configured.trim())];
+    }
+    default_dir()
+        .into_iter()
+        .chain([PathBuf::from(LEGACY_DIR)])
+        .collect()
+}
+
+pub fn validate(path: &Path) -> Result<()> {
+    let missing: Vec<&str> = REQUIRED_FILES
+        .iter()
+        .copied()
+        .filter(|file| !path.join(file).is_file())
+        .collect();
+    if !missing.is_empty() {
+        bail!("missing {}", missing.join(", "));
+    }
+
+    if !GRAPHS
+        .iter()
+        .any(|graph| graph.iter().all(|file| path.join(file).is_file()))
+    {
+        bail!("missing graph/HCLG.fst or graph/HCLr.fst + graph/Gr.fst");
+    }
+    Ok(())
+}
+
+pub fn inspect(path: &Path) -> Result<ModelInfo> {
+    validate(path)?;
+    let name = path
+        .file_name()
+        .context("model path has no name")?
+        .to_string_lossy()
+        .to_lowercase();
+    let (language, size) = parse_name(&name);
+
+    Ok(ModelInfo {
+        path: path.to_path_buf(),
+        language,
+        size,
+        sample_rate: sample_rate(path),
+        bytes: dir_size(path),
+    })
+}
+
+// Returns the valid models and a note for every directory that was skipped.
+pub fn scan(dirs: &[PathBuf]) -> (Vec<ModelInfo>, Vec<String>) {
+    let mut models = Vec::new();
+    let mut skipped = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match inspect(&path) {
+                Ok(model) => models.push(model),
+                Err(err) => skipped.push(format!("{}: {err}", path.display())),
+            }
+        }
+    }
+    (models, skipped)
+}
+
+// The requested size if present, otherwise the nearest one, preferring the
+// larger model on a tie.
+pub fn select(models: &[ModelInfo], language: Language, size: ModelSize) -> Option<&ModelInfo> {
+    models
+        .iter()
+        .filter(|model| model.language == Some(language))
+        .min_by_key(|model| {
+            (
+                (model.size as i32 - size as i32).abs(),
+                std::cmp::Reverse(model.size),
+            )
+        })
+}
+
+pub fn print_models(dirs: &[PathBuf]) {
+    let (models, skipped) = scan(dirs);
+    let searched: Vec<String> = dirs.iter().map(|dir| dir.display().to_string()).collect();
+    println!(
+        "{}{}",
+        "[*] Models in: ".bold().magenta(),
+        searched.join(", ").magenta()
+    );
+
+    if models.is_empty() {
+        println!("{}", "    [!] No models found".yellow());
+    }
+    for model in &models {
+        println!(
+            "{}",
+            format!(
+                "    {} {:<6} {:>5} Hz {:>6} MB  {}",
+                model.language.map(|l| l.code()).unwrap_or("??"),
+                model.size.name(),
+                model
+                    .sample_rate
+                    .map(|sr| sr.to_string())
+                    .unwrap_or_else(|| String::from("?")),
+                model.bytes / 1_000_000,
+                model.path.display()
+            )
+            .magenta()
+            .bold()
+        );
+    }
+    for note in skipped {
+        println!("{}", format!("    [!] Skipped {note}").yellow());
+    }
+}
+
+fn parse_name(name: &str) -> (Option<Language>, ModelSize) {
+    let tokens: Vec<&str> = name.split(['-', '_', '.']).collect();
+    let language = tokens.iter().find_map(|token| Language::from_code(token));
+    let size = if tokens.contains(&"nano") {
+        ModelSize::Nano
+    } else if tokens.contains(&"small") {
+        ModelSize::Small
+    } else {
+        ModelSize::Normal
+    };
+    (language, size)
+}
+
+fn sample_rate(path: &Path) -> Option<u32> {
+    fs::read_to_string(path.join("conf/mfcc.conf"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("--sample-frequency="))
+        .and_then(|value| value.trim().parse::<f32>().ok())
+        .map(|sr| sr as u32)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_dir;
+
+    fn fake_model(root: &Path, name: &str, files: &[&str]) -> PathBuf {
+        let path = root.join(name);
+        for file in files {
+            let file = path.join(file);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, "x").unwrap();
+        }
+        path
+    }
+
+    const COMPLETE: &[&str] = &["am/final.mdl", "conf/mfcc.conf", "graph/HCLG.fst"];
+
+    #[test]
+    fn names_give_language_and_size() {
+        assert_eq!(
+            parse_name("vosk-model-small-en-us-0.15"),
+            (Some(Language::English), ModelSize::Small)
+        );
+        assert_eq!(
+            parse_name("vosk-model-small-uk-v3-nano"),
+            (Some(Language::Ukrainian), ModelSize::Nano)
+        );
+        assert_eq!(
+            parse_name("uk-normal"),
+            (Some(Language::Ukrainian), ModelSize::Normal)
+        );
+        assert_eq!(parse_name("vosk-model-de-0.21").0, None);
+    }
+
+    #[test]
+    fn validation_reports_missing_files() {
+        let root = temp_dir("validate");
+        let ok = fake_model(&root, "en-small", COMPLETE);
+        let lookahead = fake_model(
+            &root,
+            "en-normal",
+            &[
+                "am/final.mdl",
+                "conf/mfcc.conf",
+                "graph/HCLr.fst",
+                "graph/Gr.fst",
+            ],
+        );
+        let broken = fake_model(&root, "uk-small", &["conf/mfcc.conf", "graph/HCLG.fst"]);
+
+        assert!(validate(&ok).is_ok());
+        assert!(validate(&lookahead).is_ok());
+        let err = validate(&broken).unwrap_err().to_string();
+        assert!(err.contains("am/final.mdl"), "{err}");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn scan_reads_sample_rate_and_skips_invalid_models() {
+        let root = temp_dir("scan");
+        let path = fake_model(&root, "vosk-model-small-uk-v3-small", COMPLETE);
+        fs::write(
+            path.join("conf/mfcc.conf"),
+            "--use-energy=false\n--sample-frequency=16000\n",
+        )
+        .unwrap();
+        fake_model(&root, "en-small", &["am/final.mdl"]);
+
+        let (models, skipped) = scan(&[root.clone(), root.join("missing")]);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].language, Some(Language::Ukrainian));
+        assert_eq!(models[0].sample_rate, Some(16_000));
+        assert!(models[0].bytes > 0);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains("en-small"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn select_falls_back_to_the_nearest_size() {
+        let model = |language, size| ModelInfo {
+            path: PathBuf::new(),
+            language: Some(language),
+            size,
+            sample_rate: None,
+            bytes: 0,
+        };
+        let models = vec![
+            model(Language::English, ModelSize::Nano),
+            model(Language::English, ModelSize::Normal),
+            model(Language::Ukrainian, ModelSize::Nano),
+        ];
+
+        let pick = |language, size| select(&models, language, size).map(|m| m.size);
+        assert_eq!(
+            pick(Language::English, ModelSize::Nano),
+            Some(ModelSize::Nano)
+        );
+        assert_eq!(
+            pick(Language::English, ModelSize::Small),
+            Some(ModelSize::Normal)
+        );
+        assert_eq!(
+            pick(Language::Ukrainian, ModelSize::Normal),
+            Some(ModelSize::Nano)
+        );
+        assert_eq!(select(&[], Language::English, ModelSize::Small), None);
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use vosk::{DecodingState, Recognizer};
+
+use super::{alternatives, Alternative, Transcript, Word};
+
+// What the wake and command loops need from a speech-to-text engine. Other
+// backends, e.g. a whisper.cpp CPU one, can sit behind a cargo feature.
+pub trait SpeechRecognizer {
+    // Returns true once an endpoint was detected and `result` is ready.
+    fn accept_audio(&mut self, audio: &[i16]) -> Result<bool>;
+    fn partial(&mut self) -> Transcript;
+    fn result(&mut self) -> Vec<Alternative>;
+    // Flushes whatever was heard so far.
+    fn final_result(&mut self) -> Vec<Alternative>;
+    fn reset(&mut self);
+}
+
+impl SpeechRecognizer for Recognizer {
+    fn accept_audio(&mut self, audio: &[i16]) -> Result<bool> {
+        Ok(matches!(
+            self.accept_waveform(audio)?,
+            DecodingState::Finalized
+        ))
+    }
+
+    fn partial(&mut self) -> Transcript {
+        let partial = self.partial_result();
+        Transcript {
+            text: partial.partial.to_string(),
+            words: partial
+                .partial_result
+                .iter()
+                .map(|w| Word {
+                    text: w.word.to_string(),
+                    start: w.start,
+                    end: w.end,
+                    conf: Some(w.conf),
+                })
+                .collect(),
+        }
+    }
+
+    fn result(&mut self) -> Vec<Alternative> {
+        alternatives(Recognizer::result(self))
+    }
+
+    fn final_result(&mut self) -> Vec<Alternative> {
+        alternatives(Recognizer::final_result(self))
+    }
+
+    fn reset(&mut self) {
+        Recognizer::reset(self);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+
+use super::{recognizer::SpeechRecognizer, Alternative, Transcript};
+
+enum Event {
+    Partial(Transcript),
+    Result(Transcript),
+}
+
+// Plays back predetermined partials and results once enough audio was fed.
+// Like Vosk, a result that is not read before the next chunk is lost.
+pub struct ScriptedRecognizer {
+    sample_rate: f32,
+    fed: usize,
+    events: VecDeque<(f32, Event)>,
+    partial: Transcript,
+    ready: Option<Transcript>,
+}
+
+impl ScriptedRecognizer {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            fed: 0,
+            events: VecDeque::new(),
+            partial: Transcript::default(),
+            ready: None,
+        }
+    }
+
+    pub fn partial_at(mut self, secs: f32, transcript: Transcript) -> Self {
+        self.events.push_back((secs, Event::Partial(transcript)));
+        self
+    }
+
+    pub fn result_at(mut self, secs: f32, transcript: Transcript) -> Self {
+        self.events.push_back((secs, Event::Result(transcript)));
+        self
+    }
+
+    fn take(&mut self) -> Vec<Alternative> {
+        self.partial = Transcript::default();
+        self.ready
+            .take()
+            .map(|transcript| Alternative {
+                transcript,
+                weight: 1.0,
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+impl SpeechRecognizer for ScriptedRecognizer {
+    fn accept_audio(&mut self, audio: &[i16]) -> Result<bool> {
+        self.fed += audio.len();
+        let now = self.fed as f32 / self.sample_rate;
+        self.ready = None;
+
+        while self.ready.is_none() && self.events.front().is_some_and(|(at, _)| *at <= now) {
+            match self.events.pop_front().map(|(_, event)| event) {
+                Some(Event::Partial(transcript)) => self.partial = transcript,
+                Some(Event::Result(transcript)) => self.ready = Some(transcript),
+                None => {}
+            }
+        }
+        Ok(self.ready.is_some())
+    }
+
+    fn partial(&mut self) -> Transcript {
+        self.partial.clone()
+    }
+
+    fn result(&mut self) -> Vec<Alternative> {
+        self.take()
+    }
+
+    fn final_result(&mut self) -> Vec<Alternative> {
+        self.take()
+    }
+
+    fn reset(&mut self) {
+        self.partial = Transcript::default();
+        self.ready = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> Transcript {
+        Transcript {
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn events_fire_once_their_time_is_reached() {
+        let mut rec = ScriptedRecognizer::new(1_000.0)
+            .partial_at(0.1, text("next"))
+            .result_at(0.3, text("next song"));
+
+        assert!(!rec.accept_audio(&[0; 50]).unwrap());
+        assert_eq!(rec.partial().text, "");
+        assert!(!rec.accept_audio(&[0; 100]).unwrap());
+        assert_eq!(rec.partial().text, "next");
+        assert!(rec.accept_audio(&[0; 200]).unwrap());
+        assert_eq!(rec.result()[0].transcript.text, "next song");
+        assert_eq!(rec.partial().text, "");
+        assert!(rec.result().is_empty());
+    }
+
+    #[test]
+    fn unread_result_is_dropped_by_the_next_chunk() {
+        let mut rec = ScriptedRecognizer::new(1_000.0).result_at(0.1, text("pause"));
+        assert!(rec.accept_audio(&[0; 100]).unwrap());
+        assert!(!rec.accept_audio(&[0; 100]).unwrap());
+        assert!(rec.result().is_empty());
+    }
+
+    #[test]
+    fn final_result_is_empty_without_a_scripted_result() {
+        let mut rec = ScriptedRecognizer::new(1_000.0).result_at(5.0, text("late"));
+        rec.accept_audio(&[0; 100]).unwrap();
+        assert!(rec.final_result().is_empty());
+    }
+}
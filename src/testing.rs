@@ -0,0 +1,42 @@
+use std::{fs, path::PathBuf};
+
+use crate::speech::{Transcript, Word};
+
+// A path under the system temp dir that is unique to this test run. Anything
+// an earlier run left there is removed.
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("aurora-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    let _ = fs::remove_file(&path);
+    path
+}
+
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = temp_path(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// A transcript whose words carry the given confidences.
+pub fn scored_words(words: &[(&str, f32)]) -> Transcript {
+    Transcript {
+        text: words.iter().map(|w| w.0).collect::<Vec<_>>().join(" "),
+        words: words
+            .iter()
+            .map(|&(text, conf)| Word {
+                text: text.to_string(),
+                start: 0.0,
+                end: 0.0,
+                conf: Some(conf),
+            })
+            .collect(),
+    }
+}
+
+pub fn scored(text: &str, conf: f32) -> Transcript {
+    let words: Vec<(&str, f32)> = text.split_whitespace().map(|w| (w, conf)).collect();
+    Transcript {
+        text: text.to_string(),
+        ..scored_words(&words)
+    }
+}
@@ -0,0 +1,446 @@
+use crate::{
+    normalizer::text::normalize,
+    speech::{recognizer::SpeechRecognizer, Transcript},
+};
+use anyhow::Result;
+use colored::Colorize;
+use serde::Deserialize;
+use serde_json::Value;
+use strsim::normalized_levenshtein;
+
+const DEFAULT_SENSITIVITY: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WakePhrase {
+    pub phrase: String,
+    #[serde(default = "default_sensitivity")]
+    pub sensitivity: f64,
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+fn default_sensitivity() -> f64 {
+    DEFAULT_SENSITIVITY
+}
+
+impl WakePhrase {
+    pub fn new(phrase: &str) -> Self {
+        Self {
+            phrase: phrase.to_string(),
+            sensitivity: DEFAULT_SENSITIVITY,
+            profile: None,
+        }
+    }
+
+    // Sensitivity 0.5 uses the global threshold, 1.0 doubles the allowed
+    // distance and 0.0 only accepts an exact match.
+    fn min_score(&self, threshold: f64) -> f64 {
+        let tolerance = (1.0 - threshold) * self.sensitivity.clamp(0.0, 1.0) * 2.0;
+        (1.0 - tolerance).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WakeMatch {
+    pub phrase: String,
+    pub profile: Option<String>,
+    pub score: f64,
+    pub command: String,
+    pub end: Option<f32>,
+}
+
+pub struct WakeDetector {
+    phrases: Vec<(WakePhrase, Vec<String>)>,
+    threshold: f64,
+}
+
+impl WakeDetector {
+    pub fn new(phrases: Vec<WakePhrase>, threshold: f64) -> Self {
+        let phrases = phrases
+            .into_iter()
+            .map(|p| {
+                let tokens = normalize(&p.phrase)
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect();
+                (p, tokens)
+            })
+            .filter(|(_, tokens): &(WakePhrase, Vec<String>)| !tokens.is_empty())
+            .collect();
+        Self { phrases, threshold }
+    }
+
+    pub fn from_settings(wake_words: Option<Value>, language: &str, threshold: f64) -> Self {
+        let configured = wake_words
+            .and_then(|v| v.get(language).cloned())
+            .map(serde_json::from_value::<Vec<WakePhrase>>);
+
+        let phrases = match configured {
+            Some(Ok(phrases)) if !phrases.is_empty() => phrases,
+            Some(Err(err)) => {
+                println!(
+                    "{}\n{}",
+                    format!("[!] Invalid wake_words for {language}: {err}").red(),
+                    "[*] Using default wake word".cyan()
+                );
+                default_phrases(language)
+            }
+            _ => default_phrases(language),
+        };
+
+        Self::new(phrases, threshold)
+    }
+
+    pub fn phrases(&self) -> impl Iterator<Item = &str> {
+        self.phrases.iter().map(|(p, _)| p.phrase.as_str())
+    }
+
+    pub fn grammar(&self) -> Vec<String> {
+        let mut grammar: Vec<String> = self.phrases.iter().map(|(_, t)| t.join(" ")).collect();
+        grammar.push(String::from("[unk]"));
+        grammar
+    }
+
+    pub fn detect(&self, transcript: &Transcript) -> Option<WakeMatch> {
+        let timed = !transcript.words.is_empty();
+        let tokens: Vec<String> = if timed {
+            transcript
+                .words
+                .iter()
+                .map(|w| normalize(&w.text))
+                .collect()
+        } else {
+            normalize(&transcript.text)
+                .split_whitespace()
+                .map(String::from)
+                .collect()
+        };
+
+        let mut best: Option<(f64, &WakePhrase, usize)> = None;
+        for (phrase, wake) in &self.phrases {
+            let Some((score, end)) = find_phrase(&tokens, wake) else {
+                continue;
+            };
+            if score >= phrase.min_score(self.threshold) && best.is_none_or(|b| score > b.0) {
+                best = Some((score, phrase, end));
+            }
+        }
+        let (score, phrase, end) = best?;
+
+        let wake_end = timed.then(|| transcript.words[end - 1].end);
+        let command = match wake_end {
+            // Timings also drop filler the recognizer glued onto the wake word.
+            Some(wake_end) => transcript
+                .words
+                .iter()
+                .skip(end)
+                .filter(|w| w.start >= wake_end)
+                .map(|w| w.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => tokens[end..].join(" "),
+        };
+
+        Some(WakeMatch {
+            phrase: phrase.phrase.clone(),
+            profile: phrase.profile.clone(),
+            score,
+            command,
+            end: wake_end,
+        })
+    }
+}
+
+pub struct WakeHit {
+    pub wake: WakeMatch,
+    pub heard: String,
+    // Audio that followed the wake word in the same utterance.
+    pub rest: Vec<i16>,
+}
+
+pub struct WakeListener {
+    rec: Box<dyn SpeechRecognizer>,
+    detector: WakeDetector,
+    sample_rate: f32,
+    fed: u64,
+    utterance: Vec<i16>,
+    utterance_start: u64,
+}
+
+impl WakeListener {
+    pub fn new(rec: Box<dyn SpeechRecognizer>, detector: WakeDetector, sample_rate: f32) -> Self {
+        Self {
+            rec,
+            detector,
+            sample_rate,
+            fed: 0,
+            utterance: Vec::new(),
+            utterance_start: 0,
+        }
+    }
+
+    pub fn accept(&mut self, audio: &[i16], end_of_speech: bool) -> Result<Option<WakeHit>> {
+        if self.utterance.is_empty() {
+            self.utterance_start = self.fed;
+        }
+        self.utterance.extend_from_slice(audio);
+        self.fed += audio.len() as u64;
+
+        let alternatives = if self.rec.accept_audio(audio)? {
+            self.rec.result()
+        } else if end_of_speech {
+            self.rec.final_result()
+        } else {
+            return Ok(None);
+        };
+        let transcript = alternatives
+            .into_iter()
+            .next()
+            .map(|alt| alt.transcript)
+            .unwrap_or_default();
+
+        let utterance = std::mem::take(&mut self.utterance);
+        let Some(wake) = self.detector.detect(&transcript) else {
+            return Ok(None);
+        };
+
+        // Word times count from the start of the stream, not the utterance.
+        let rest = match wake.end {
+            Some(end) => {
+                let at = ((end * self.sample_rate) as u64).saturating_sub(self.utterance_start);
+                utterance[(at as usize).min(utterance.len())..].to_vec()
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Some(WakeHit {
+            wake,
+            heard: transcript.text,
+            rest,
+        }))
+    }
+
+    pub fn reset(&mut self) {
+        self.rec.reset();
+        self.utterance.clear();
+    }
+}
+
+fn default_phrases(language: &str) -> Vec<WakePhrase> {
+    match language {
+        "uk" => vec![WakePhrase::new("аврора")],
+        _ => vec![WakePhrase::new("aurora")],
+    }
+}
+
+// Best window of whole tokens, returned as (score, end token index).
+fn find_phrase(tokens: &[String], wake: &[String]) -> Option<(f64, usize)> {
+    let max_len = tokens.len().min(wake.len() + 1);
+    let wake = wake.join(" ");
+    let mut best: Option<(f64, usize)> = None;
+
+    for len in 1..=max_len {
+        for start in 0..=tokens.len() - len {
+            let window = tokens[start..start + len].join(" ");
+            let score = normalized_levenshtein(&window, &wake);
+            if best.is_none_or(|b| score > b.0) {
+                best = Some((score, start + len));
+            }
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speech::{scripted::ScriptedRecognizer, Word};
+
+    fn timed(words: &[(&str, f32, f32)]) -> Transcript {
+        Transcript {
+            text: words.iter().map(|w| w.0).collect::<Vec<_>>().join(" "),
+            words: words
+                .iter()
+                .map(|&(text, start, end)| Word {
+                    text: text.to_string(),
+                    start,
+                    end,
+                    conf: Some(1.0),
+                })
+                .collect(),
+        }
+    }
+
+    fn text(text: &str) -> Transcript {
+        Transcript {
+            text: text.to_string(),
+            words: Vec::new(),
+        }
+    }
+
+    fn detector(phrases: &[&str]) -> WakeDetector {
+        WakeDetector::new(phrases.iter().map(|p| WakePhrase::new(p)).collect(), 0.8)
+    }
+
+    #[test]
+    fn command_after_wake_word_is_kept() {
+        let t = timed(&[
+            ("aurora", 0.2, 0.7),
+            ("open", 0.9, 1.1),
+            ("firefox", 1.1, 1.6),
+        ]);
+        let m = detector(&["Aurora"]).detect(&t).unwrap();
+        assert_eq!(m.command, "open firefox");
+        assert_eq!(m.score, 1.0);
+    }
+
+    #[test]
+    fn words_before_wake_word_are_dropped() {
+        let t = timed(&[
+            ("ну", 0.0, 0.2),
+            ("аврора", 0.3, 0.8),
+            ("відкрий", 0.9, 1.3),
+            ("термінал", 1.3, 1.9),
+        ]);
+        let m = detector(&["аврора"]).detect(&t).unwrap();
+        assert_eq!(m.command, "відкрий термінал");
+    }
+
+    #[test]
+    fn wake_word_alone_gives_empty_command() {
+        let t = timed(&[("aurora", 0.2, 0.7)]);
+        assert_eq!(detector(&["aurora"]).detect(&t).unwrap().command, "");
+    }
+
+    #[test]
+    fn multi_word_wake_phrase() {
+        let t = timed(&[("hey", 0.0, 0.2), ("aurora", 0.2, 0.7), ("pause", 0.8, 1.2)]);
+        let m = detector(&["hey aurora"]).detect(&t).unwrap();
+        assert_eq!(m.command, "pause");
+    }
+
+    #[test]
+    fn missing_wake_word() {
+        let t = timed(&[("open", 0.0, 0.2), ("firefox", 0.3, 0.7)]);
+        assert_eq!(detector(&["aurora"]).detect(&t), None);
+    }
+
+    #[test]
+    fn falls_back_to_text_without_timings() {
+        let d = detector(&["aurora"]);
+        assert_eq!(
+            d.detect(&text("aurora open firefox")).unwrap().command,
+            "open firefox"
+        );
+        assert_eq!(d.detect(&text("open firefox")), None);
+    }
+
+    #[test]
+    fn matching_is_token_aware() {
+        let d = detector(&["аврора"]);
+        assert_eq!(d.detect(&text("авроральний спалах")), None);
+        assert_eq!(
+            d.detect(&text("аврор відкрий термінал")).unwrap().command,
+            "відкрий термінал"
+        );
+    }
+
+    #[test]
+    fn split_asr_variant_can_be_listed() {
+        let d = detector(&["aurora", "a roar a"]);
+        let m = d.detect(&text("a roar a next song")).unwrap();
+        assert_eq!(m.phrase, "a roar a");
+        assert_eq!(m.command, "next song");
+    }
+
+    #[test]
+    fn sensitivity_changes_required_score() {
+        let strict = WakePhrase {
+            sensitivity: 0.0,
+            ..WakePhrase::new("аврора")
+        };
+        let loose = WakePhrase {
+            sensitivity: 1.0,
+            ..WakePhrase::new("аврора")
+        };
+        assert_eq!(strict.min_score(0.8), 1.0);
+        assert!((loose.min_score(0.8) - 0.6).abs() < 1e-9);
+        assert!((WakePhrase::new("x").min_score(0.8) - 0.8).abs() < 1e-9);
+
+        let t = text("аврор пауза");
+        assert!(WakeDetector::new(vec![strict], 0.8).detect(&t).is_none());
+        assert!(WakeDetector::new(vec![loose], 0.8).detect(&t).is_some());
+    }
+
+    #[test]
+    fn best_phrase_wins_and_carries_profile() {
+        let work = WakePhrase {
+            profile: Some(String::from("work")),
+            ..WakePhrase::new("computer")
+        };
+        let d = WakeDetector::new(vec![WakePhrase::new("aurora"), work], 0.8);
+        let m = d.detect(&text("computer open terminal")).unwrap();
+        assert_eq!(m.profile.as_deref(), Some("work"));
+        assert_eq!(m.command, "open terminal");
+    }
+
+    #[test]
+    fn timed_match_reports_wake_end() {
+        let t = timed(&[("aurora", 3.2, 3.7), ("pause", 3.9, 4.2)]);
+        assert_eq!(detector(&["aurora"]).detect(&t).unwrap().end, Some(3.7));
+        assert_eq!(
+            detector(&["aurora"])
+                .detect(&text("aurora pause"))
+                .unwrap()
+                .end,
+            None
+        );
+    }
+
+    #[test]
+    fn grammar_lists_phrases_and_unknown() {
+        let d = detector(&["Aurora", "Hey, Aurora"]);
+        assert_eq!(d.grammar(), vec!["aurora", "hey aurora", "[unk]"]);
+    }
+
+    #[test]
+    fn loads_phrases_from_settings() {
+        let value = serde_json::json!({
+            "en": [
+                { "phrase": "aurora" },
+                { "phrase": "jarvis", "sensitivity": 0.2, "profile": "butler" }
+            ]
+        });
+        let d = WakeDetector::from_settings(Some(value.clone()), "en", 0.8);
+        assert_eq!(d.phrases().collect::<Vec<_>>(), vec!["aurora", "jarvis"]);
+        assert_eq!(d.phrases[1].0.sensitivity, 0.2);
+
+        let d = WakeDetector::from_settings(Some(value), "uk", 0.8);
+        assert_eq!(d.phrases().collect::<Vec<_>>(), vec!["аврора"]);
+
+        let d = WakeDetector::from_settings(None, "en", 0.8);
+        assert_eq!(d.phrases().collect::<Vec<_>>(), vec!["aurora"]);
+    }
+
+    #[test]
+    fn listener_hands_over_audio_after_the_wake_word() {
+        let rec = ScriptedRecognizer::new(1_000.0)
+            .result_at(1.0, timed(&[("aurora", 0.2, 0.7), ("pause", 0.8, 1.0)]));
+        let mut listener = WakeListener::new(Box::new(rec), detector(&["aurora"]), 1_000.0);
+        let audio: Vec<i16> = (0..1_000).collect();
+
+        assert!(listener.accept(&audio[..500], false).unwrap().is_none());
+        let hit = listener.accept(&audio[500..], false).unwrap().unwrap();
+        assert_eq!(hit.wake.command, "pause");
+        assert_eq!(hit.rest.len(), 300);
+        assert_eq!(hit.rest[0], 700);
+    }
+
+    #[test]
+    fn listener_ignores_other_speech() {
+        let rec = ScriptedRecognizer::new(1_000.0).result_at(0.5, text("hello there"));
+        let mut listener = WakeListener::new(Box::new(rec), detector(&["aurora"]), 1_000.0);
+        assert!(listener.accept(&[0; 600], true).unwrap().is_none());
+    }
+}